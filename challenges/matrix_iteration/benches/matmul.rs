@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use matrix_iteration::{Matrix, Num};
+
+const N: usize = 1000;
+const RUNS: u32 = 3;
+
+fn pseudo_random<T: Num>(n: usize, seed: u32, convert: fn(f64) -> T) -> Matrix<T> {
+    let mut state = seed;
+    let data: Vec<T> = (0..n * n)
+        .map(|_| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            convert((state >> 16) as f64 / 65536.0 - 0.5)
+        })
+        .collect();
+    Matrix::new(n, n, &data)
+}
+
+fn measure<T, F: FnMut() -> T>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        std::hint::black_box(f());
+    }
+    start.elapsed() / RUNS
+}
+
+fn report<T: Num + Send + Sync>(name: &str, a: &Matrix<T>, b: &Matrix<T>) {
+    let naive = measure(|| a.mul_naive(b).unwrap());
+    let blocked = measure(|| a.mul_blocked(b).unwrap());
+    let parallel = measure(|| a.par_mul(b, 0).unwrap());
+
+    println!(
+        "{:<4} naive {:>10.2?}   blocked {:>10.2?} ({:.2}x)   parallel {:>10.2?} ({:.2}x)",
+        name,
+        naive,
+        blocked,
+        naive.as_secs_f64() / blocked.as_secs_f64(),
+        parallel,
+        naive.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
+
+fn main() {
+    println!("{0}x{0} by {0}x{0}, {1} runs each, all cores", N, RUNS);
+
+    report("f32", &pseudo_random(N, 1, |x| x as f32), &pseudo_random(N, 2, |x| x as f32));
+    report("f64", &pseudo_random(N, 1, |x| x), &pseudo_random(N, 2, |x| x));
+}
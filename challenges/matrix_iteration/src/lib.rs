@@ -1,4 +1,17 @@
-#[derive(Debug)]
+pub mod linalg;
+pub mod matmul;
+pub mod shape;
+pub mod view;
+
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+pub use linalg::{Float, LinalgError, Lu, Num};
+pub use shape::ShapeError;
+pub use view::{MatrixView, MatrixViewMut};
+use view::{Layout, Lines};
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Matrix<T> {
     rows: usize,
     cols: usize,
@@ -14,70 +27,536 @@ impl<T: Clone> Matrix<T> {
     /// така че си ги клонирайте и си ги наредете както ви е удобно.
     ///
     pub fn new(rows: usize, cols: usize, data: &[T]) -> Self {
-        assert!(rows * cols == data.len());
+        match Matrix::try_new(rows, cols, data) {
+            Ok(result) => result,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Като `new`, но вместо да panic-не при грешни размери, връща `ShapeError`.
+    ///
+    pub fn try_new(rows: usize, cols: usize, data: &[T]) -> Result<Self, ShapeError> {
+        shape::check_len(rows, cols, data.len())?;
+        Ok(Matrix { rows, cols, data: data.to_vec() })
+    }
+}
 
-        Matrix {
-            rows,
-            cols,
-            data: data.clone()
-                .iter()
-                .map(|x| x.to_owned())
-                .collect()
+impl<T> Matrix<T> {
+    /// Като `try_new`, но взима данните наготово, без да ги клонира.
+    ///
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Result<Self, ShapeError> {
+        shape::check_len(rows, cols, data.len())?;
+        Ok(Matrix { rows, cols, data })
+    }
+
+    /// Матрица, в която елементът `(i, j)` е `f(i, j)`. Елементите се пресмятат по редове.
+    ///
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Result<Self, ShapeError> {
+        let mut data = Vec::with_capacity(shape::checked_len(rows, cols)?);
+        for i in 0..rows {
+            data.extend((0..cols).map(|j| f(i, j)));
         }
+        Ok(Matrix { rows, cols, data })
+    }
+
+    /// Брой редове и брой колони.
+    ///
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Изглед към цялата матрица.
+    ///
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView::new(&self.data, Layout::row_major(self.rows, self.cols))
+    }
+
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut::new(&mut self.data, Layout::row_major(self.rows, self.cols))
+    }
+
+    pub fn transpose(&self) -> MatrixView<'_, T> {
+        self.view().transpose()
+    }
+
+    pub fn submatrix(&self, rows: Range<usize>, cols: Range<usize>) -> MatrixView<'_, T> {
+        self.view().submatrix(rows, cols)
+    }
+
+    pub fn try_submatrix(&self, rows: Range<usize>, cols: Range<usize>) -> Result<MatrixView<'_, T>, ShapeError> {
+        self.view().try_submatrix(rows, cols)
+    }
+
+    pub fn row(&self, i: usize) -> MatrixView<'_, T> {
+        self.view().row(i)
+    }
+
+    pub fn col(&self, j: usize) -> MatrixView<'_, T> {
+        self.view().col(j)
+    }
+
+    pub fn try_row(&self, i: usize) -> Result<MatrixView<'_, T>, ShapeError> {
+        self.view().try_row(i)
+    }
+
+    pub fn try_col(&self, j: usize) -> Result<MatrixView<'_, T>, ShapeError> {
+        self.view().try_col(j)
+    }
+
+    pub fn by_row(&self) -> RowIter<'_, T> {
+        self.view().by_row()
+    }
+
+    pub fn by_col(&self) -> ColIter<'_, T> {
+        self.view().by_col()
+    }
+
+    pub fn by_row_mut(&mut self) -> RowIterMut<'_, T> {
+        self.view_mut().by_row_mut()
+    }
+
+    pub fn by_col_mut(&mut self) -> ColIterMut<'_, T> {
+        self.view_mut().by_col_mut()
+    }
+}
+
+pub struct RowIter<'a, T> {
+    // Позициите в обхождането по редове на следващия елемент отпред и след последния отзад
+    front: usize,
+    back: usize,
+    view: MatrixView<'a, T>,
+}
+
+impl<'a, T> RowIter<'a, T> {
+    fn cell(&self, curr: usize) -> &'a T {
+        let cols = self.view.shape().1;
+        self.view.get(curr / cols, curr % cols).unwrap()
+    }
+}
+
+impl<'a, T> Iterator for RowIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.cell(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl<T> DoubleEndedIterator for RowIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.cell(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for RowIter<'_, T> {}
+
+impl<T> FusedIterator for RowIter<'_, T> {}
+
+pub struct ColIter<'a, T> {
+    // Позициите в обхождането по колони
+    front: usize,
+    back: usize,
+    view: MatrixView<'a, T>,
+}
+
+impl<'a, T> ColIter<'a, T> {
+    fn cell(&self, curr: usize) -> &'a T {
+        let rows = self.view.shape().0;
+        self.view.get(curr % rows, curr / rows).unwrap()
+    }
+}
+
+impl<'a, T> Iterator for ColIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.cell(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl<T> DoubleEndedIterator for ColIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.cell(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for ColIter<'_, T> {}
+
+impl<T> FusedIterator for ColIter<'_, T> {}
+
+pub struct RowIterMut<'a, T> {
+    lines: Lines<'a, T>,
+}
+
+impl<'a, T> Iterator for RowIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.lines.len(), Some(self.lines.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for RowIterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.lines.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for RowIterMut<'_, T> {}
+
+impl<T> FusedIterator for RowIterMut<'_, T> {}
+
+pub struct ColIterMut<'a, T> {
+    lines: Lines<'a, T>,
+}
+
+impl<'a, T> Iterator for ColIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.lines.len(), Some(self.lines.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for ColIterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.lines.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for ColIterMut<'_, T> {}
+
+impl<T> FusedIterator for ColIterMut<'_, T> {}
+
+impl<T> Matrix<T> {
+    pub fn rows(&self) -> Rows<'_, T> {
+        Rows {
+            front: 0,
+            back: self.rows,
+            matrix: self,
+        }
+    }
+
+    pub fn cols(&self) -> Cols<'_, T> {
+        Cols {
+            front: 0,
+            back: self.cols,
+            matrix: self,
+        }
+    }
+
+    /// Елементите `(i, i)`, докато не свърши по-малкото измерение.
+    ///
+    pub fn diagonal(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + FusedIterator {
+        (0..self.rows.min(self.cols)).map(move |i| &self.data[i * self.cols + i])
+    }
+
+    /// Елементите `(i, cols - 1 - i)` -- от горния десен ъгъл надолу и наляво.
+    ///
+    pub fn anti_diagonal(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + FusedIterator {
+        (0..self.rows.min(self.cols)).map(move |i| &self.data[i * self.cols + self.cols - 1 - i])
     }
 
-    pub fn by_row(&self) -> RowIter<T> {
-        RowIter {
-            curr: 0,
+    /// Обхождане по анти-диагоналите, като посоката се сменя на всеки -- както при JPEG:
+    ///
+    /// | 1 2 6 |
+    /// | 3 5 7 |
+    /// | 4 8 9 |
+    ///
+    pub fn zig_zag(&self) -> ZigZag<'_, T> {
+        ZigZag {
+            row: 0,
+            col: 0,
+            remaining: self.data.len(),
             matrix: self,
         }
     }
 
-    pub fn by_col(&self) -> ColIter<T> {
-        ColIter {
-            curr: 0,
+    /// Обхождане по спирала, по часовниковата стрелка от горния ляв ъгъл:
+    ///
+    /// | 1 2 3 |
+    /// | 8 9 4 |
+    /// | 7 6 5 |
+    ///
+    pub fn spiral(&self) -> Spiral<'_, T> {
+        Spiral {
+            row: 0,
+            col: 0,
+            direction: Direction::Right,
+            top: 0,
+            bottom: self.rows.saturating_sub(1),
+            left: 0,
+            right: self.cols.saturating_sub(1),
+            remaining: self.data.len(),
             matrix: self,
         }
     }
 }
 
-pub struct RowIter<'a, T> {
-    // каквото ви трябва
-    curr: usize,
+pub struct Rows<'a, T> {
+    front: usize,
+    back: usize,
     matrix: &'a Matrix<T>,
 }
 
-impl<'a, T> Iterator for RowIter<'a, T> {
+impl<'a, T> Rows<'a, T> {
+    fn row(&self, i: usize) -> &'a [T] {
+        let matrix: &'a Matrix<T> = self.matrix;
+        &matrix.data[i * matrix.cols..(i + 1) * matrix.cols]
+    }
+}
+
+impl<'a, T> Iterator for Rows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.row(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl<T> DoubleEndedIterator for Rows<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.row(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for Rows<'_, T> {}
+
+impl<T> FusedIterator for Rows<'_, T> {}
+
+/// Колоните на матрица като изгледи rows x 1 -- както `Matrix::col`, без копиране.
+///
+pub struct Cols<'a, T> {
+    front: usize,
+    back: usize,
+    matrix: &'a Matrix<T>,
+}
+
+impl<'a, T> Iterator for Cols<'a, T> {
+    type Item = MatrixView<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.matrix.view().col(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl<T> DoubleEndedIterator for Cols<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.matrix.view().col(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for Cols<'_, T> {}
+
+impl<T> FusedIterator for Cols<'_, T> {}
+
+pub struct ZigZag<'a, T> {
+    row: usize,
+    col: usize,
+    remaining: usize,
+    matrix: &'a Matrix<T>,
+}
+
+impl<'a, T> Iterator for ZigZag<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let result = match self.curr < self.matrix.rows * self.matrix.cols {
-            true => Some(&self.matrix.data[self.curr]),
-            _ => None,
-        };
-        self.curr += 1;
-        result
+        if self.remaining == 0 {
+            return None;
+        }
+        let (rows, cols) = (self.matrix.rows, self.matrix.cols);
+        let result = &self.matrix.data[self.row * cols + self.col];
+        self.remaining -= 1;
+
+        // По четните анти-диагонали вървим нагоре и надясно, по нечетните -- надолу и наляво
+        if (self.row + self.col).is_multiple_of(2) {
+            if self.col == cols - 1 {
+                self.row += 1;
+            } else if self.row == 0 {
+                self.col += 1;
+            } else {
+                self.row -= 1;
+                self.col += 1;
+            }
+        } else if self.row == rows - 1 {
+            self.col += 1;
+        } else if self.col == 0 {
+            self.row += 1;
+        } else {
+            self.row += 1;
+            self.col -= 1;
+        }
+
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
-pub struct ColIter<'a, T> {
-    // каквото ви трябва
-    curr: usize,
+impl<T> ExactSizeIterator for ZigZag<'_, T> {}
+
+impl<T> FusedIterator for ZigZag<'_, T> {}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+/// Спиралата минава по текущата рамка `top..=bottom` x `left..=right` и я стеснява при всеки завой.
+///
+pub struct Spiral<'a, T> {
+    row: usize,
+    col: usize,
+    direction: Direction,
+    top: usize,
+    bottom: usize,
+    left: usize,
+    right: usize,
+    remaining: usize,
     matrix: &'a Matrix<T>,
 }
 
-impl<'a, T> Iterator for ColIter<'a, T> {
+impl<'a, T> Iterator for Spiral<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let cell = (self.curr % self.matrix.rows) * self.matrix.cols + self.curr / self.matrix.rows;
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = &self.matrix.data[self.row * self.matrix.cols + self.col];
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            return Some(result);
+        }
+
+        match self.direction {
+            Direction::Right if self.col < self.right => self.col += 1,
+            Direction::Right => {
+                self.top += 1;
+                self.row += 1;
+                self.direction = Direction::Down;
+            }
+            Direction::Down if self.row < self.bottom => self.row += 1,
+            Direction::Down => {
+                self.right -= 1;
+                self.col -= 1;
+                self.direction = Direction::Left;
+            }
+            Direction::Left if self.col > self.left => self.col -= 1,
+            Direction::Left => {
+                self.bottom -= 1;
+                self.row -= 1;
+                self.direction = Direction::Up;
+            }
+            Direction::Up if self.row > self.top => self.row -= 1,
+            Direction::Up => {
+                self.left += 1;
+                self.col += 1;
+                self.direction = Direction::Right;
+            }
+        }
 
-        let result = match self.curr < self.matrix.rows * self.matrix.cols {
-            true => Some(&self.matrix.data[cell]),
-            _ => None,
-        };
-        self.curr += 1;
-        result
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Spiral<'_, T> {}
+
+impl<T> FusedIterator for Spiral<'_, T> {}
+
+/// Елементите по редове, с ownership.
+///
+impl<T> IntoIterator for Matrix<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Matrix<T> {
+    type Item = &'a T;
+    type IntoIter = RowIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.by_row()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Matrix<T> {
+    type Item = &'a mut T;
+    type IntoIter = RowIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.by_row_mut()
     }
 }
 
@@ -124,4 +603,141 @@ mod mytests {
     fn test_invalid_data_size() {
         assert!(std::panic::catch_unwind(|| Matrix::new(1,1,&[1,2])).is_err());
     }
+
+    #[test]
+    fn test_fallible_constructors() {
+        let mat = Matrix::try_new(2, 3, &[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]), Ok(mat.clone()));
+        assert_eq!(Matrix::from_fn(2, 3, |i, j| 3 * i + j + 1), Ok(mat.clone()));
+        assert_eq!(Matrix::from_fn(0, 4, |_, _| 1).map(|m| m.shape()), Ok((0, 4)));
+
+        assert_eq!(Matrix::try_new(1, 1, &[1, 2]), Err(ShapeError::DataLength { rows: 1, cols: 1, len: 2 }));
+        assert_eq!(Matrix::from_vec(2, 2, vec![1]).unwrap_err().to_string(), "a 2x2 matrix needs 4 elements, got 1");
+
+        let huge = usize::MAX / 2;
+        assert_eq!(Matrix::<i32>::from_vec(huge, 3, vec![]), Err(ShapeError::Overflow { rows: huge, cols: 3 }));
+        assert_eq!(Matrix::from_fn(3, huge, |_, _| 0), Err(ShapeError::Overflow { rows: 3, cols: huge }));
+        assert!(std::panic::catch_unwind(|| Matrix::new(huge, 4, &[1])).is_err());
+
+        assert_eq!(mat.try_submatrix(1..2, 0..3).map(|v| v.shape()), Ok((1, 3)));
+        let error = ShapeError::OutOfBounds { rows: 1..3, cols: 0..1, shape: (2, 3) };
+        assert_eq!(mat.try_submatrix(1..3, 0..1).unwrap_err(), error);
+        let message = mat.transpose().try_submatrix(0..3, 2..3).unwrap_err().to_string();
+        assert_eq!(message, "submatrix [0..3, 2..3] is out of bounds for a 3x2 matrix");
+    }
+
+    #[test]
+    fn test_double_ended() {
+        let mat = Matrix::new(2, 3, &[1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(mat.by_row().rev().collect::<Vec<_>>(), vec![&6, &5, &4, &3, &2, &1]);
+        assert_eq!(mat.by_col().rev().collect::<Vec<_>>(), vec![&6, &3, &5, &2, &4, &1]);
+
+        let mut it = mat.by_col();
+        assert_eq!(it.len(), 6);
+        assert_eq!((it.next(), it.next_back(), it.next()), (Some(&1), Some(&6), Some(&4)));
+        assert_eq!(it.len(), 3);
+        assert_eq!(it.collect::<Vec<_>>(), vec![&2, &5, &3]);
+
+        let pairs: Vec<_> = mat.by_row().zip(mat.by_col().rev()).map(|(a, b)| a * b).collect();
+        assert_eq!(pairs, vec![6, 6, 15, 8, 20, 6]);
+    }
+
+    #[test]
+    fn test_mut() {
+        let mut mat = Matrix::new(2, 3, &[1, 2, 3, 4, 5, 6]);
+
+        for (i, x) in mat.by_col_mut().enumerate() {
+            *x = *x * 10 + i;
+        }
+        assert_eq!(mat.by_row().collect::<Vec<_>>(), vec![&10, &22, &34, &41, &53, &65]);
+
+        let mut it = mat.by_col_mut();
+        assert_eq!(it.len(), 6);
+        *it.next_back().unwrap() = 0;
+        *it.next().unwrap() = 0;
+        *it.next_back().unwrap() = 0;
+        assert_eq!(it.len(), 3);
+        drop(it);
+        assert_eq!(mat.by_row().collect::<Vec<_>>(), vec![&0, &22, &0, &41, &53, &0]);
+
+        mat.by_row_mut().rev().take(2).for_each(|x| *x = 1);
+        assert_eq!(mat.by_col().collect::<Vec<_>>(), vec![&0, &41, &22, &1, &0, &1]);
+
+        let mut empty = Matrix::<i32>::new(0, 3, &[]);
+        assert_eq!(empty.by_col_mut().next(), None);
+        assert_eq!(empty.by_col().next_back(), None);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut mat = Matrix::new(2, 2, &[String::from("a"), String::from("b"), String::from("c"), String::from("d")]);
+
+        for s in &mut mat {
+            s.push('!');
+        }
+        let mut total = String::new();
+        for s in &mat {
+            total += s;
+        }
+        assert_eq!(total, "a!b!c!d!");
+        assert_eq!(mat.into_iter().rev().collect::<Vec<_>>(), vec!["d!", "c!", "b!", "a!"]);
+    }
+
+    #[test]
+    fn test_rows_and_cols() {
+        let mat = Matrix::new(2, 3, &[1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(mat.rows().collect::<Vec<_>>(), vec![&[1, 2, 3][..], &[4, 5, 6][..]]);
+        assert_eq!(mat.rows().next_back(), Some(&[4, 5, 6][..]));
+
+        let cols: Vec<Vec<_>> = mat.cols().map(|col| col.by_row().copied().collect()).collect();
+        assert_eq!(cols, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+
+        let last = mat.cols().next_back().unwrap();
+        assert_eq!((last.shape(), last.get(0, 0), last.get(1, 0), last.get(2, 0)), ((2, 1), Some(&3), Some(&6), None));
+        assert_eq!(mat.cols().len(), 3);
+
+        let empty = Matrix::<i32>::new(2, 0, &[]);
+        assert_eq!(empty.rows().collect::<Vec<_>>(), vec![&[] as &[i32], &[]]);
+        assert_eq!(empty.cols().next().map(|c| c.shape()), None);
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let mat = Matrix::new(3, 4, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+
+        assert_eq!(mat.diagonal().collect::<Vec<_>>(), vec![&1, &6, &11]);
+        assert_eq!(mat.anti_diagonal().collect::<Vec<_>>(), vec![&4, &7, &10]);
+        assert_eq!(mat.anti_diagonal().rev().collect::<Vec<_>>(), vec![&10, &7, &4]);
+        assert_eq!(Matrix::new(3, 1, &[1, 2, 3]).anti_diagonal().collect::<Vec<_>>(), vec![&1]);
+        assert_eq!(Matrix::<i32>::new(0, 0, &[]).diagonal().len(), 0);
+    }
+
+    #[test]
+    fn test_zig_zag() {
+        let mat = Matrix::new(3, 3, &[1, 2, 6, 3, 5, 7, 4, 8, 9]);
+        assert_eq!(mat.zig_zag().copied().collect::<Vec<_>>(), (1..=9).collect::<Vec<_>>());
+
+        let mat = Matrix::new(2, 4, &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(mat.zig_zag().copied().collect::<Vec<_>>(), vec![1, 2, 5, 6, 3, 4, 7, 8]);
+
+        let mat = Matrix::new(3, 1, &[1, 2, 3]);
+        assert_eq!(mat.zig_zag().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_spiral() {
+        let mat = Matrix::new(3, 3, &[1, 2, 3, 8, 9, 4, 7, 6, 5]);
+        assert_eq!(mat.spiral().copied().collect::<Vec<_>>(), (1..=9).collect::<Vec<_>>());
+
+        let mat = Matrix::new(3, 4, &[1, 2, 3, 4, 10, 11, 12, 5, 9, 8, 7, 6]);
+        assert_eq!(mat.spiral().copied().collect::<Vec<_>>(), (1..=12).collect::<Vec<_>>());
+
+        let mat = Matrix::new(4, 2, &[1, 2, 8, 3, 7, 4, 6, 5]);
+        assert_eq!(mat.spiral().copied().collect::<Vec<_>>(), (1..=8).collect::<Vec<_>>());
+
+        assert_eq!(Matrix::new(3, 1, &[1, 2, 3]).spiral().len(), 3);
+        assert_eq!(Matrix::<i32>::new(0, 5, &[]).spiral().next(), None);
+    }
 }
@@ -0,0 +1,426 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::shape::checked_len;
+use crate::{Matrix, ShapeError};
+
+/// Числата, с които можем да смятаме -- нещо като `num_traits::Num`, само с каквото ни трябва.
+///
+pub trait Num: Copy + PartialEq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+}
+
+/// Числа с плаваща запетая -- за LU, детерминанта, обратна матрица и решаване на системи.
+///
+pub trait Float: Num + PartialOrd + Div<Output = Self> + Neg<Output = Self> {
+    fn abs(self) -> Self;
+    fn epsilon() -> Self;
+    fn from_usize(n: usize) -> Self;
+}
+
+macro_rules! impl_num {
+    ($($t:ty),*) => {
+        $(
+            impl Num for $t {
+                fn zero() -> Self {
+                    0 as $t
+                }
+
+                fn one() -> Self {
+                    1 as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_num!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+macro_rules! impl_float {
+    ($($t:ident),*) => {
+        $(
+            impl Float for $t {
+                fn abs(self) -> Self {
+                    $t::abs(self)
+                }
+
+                fn epsilon() -> Self {
+                    $t::EPSILON
+                }
+
+                fn from_usize(n: usize) -> Self {
+                    n as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinalgError {
+    /// Размерите на операндите не позволяват операцията.
+    Shape(ShapeError),
+    Singular,
+}
+
+impl From<ShapeError> for LinalgError {
+    fn from(e: ShapeError) -> Self {
+        LinalgError::Shape(e)
+    }
+}
+
+impl Display for LinalgError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LinalgError::Shape(e) => e.fmt(f),
+            LinalgError::Singular => write!(f, "matrix is singular"),
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LinalgError::Shape(e) => Some(e),
+            LinalgError::Singular => None,
+        }
+    }
+}
+
+impl<T: Num> Matrix<T> {
+    /// Panic-ва, ако `rows * cols` препълва `usize`. Без panic -- с `Matrix::try_zeros`.
+    ///
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        match Matrix::try_zeros(rows, cols) {
+            Ok(result) => result,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Като `zeros`, но вместо да panic-не, връща `ShapeError::Overflow`.
+    ///
+    pub fn try_zeros(rows: usize, cols: usize) -> Result<Self, ShapeError> {
+        Ok(Matrix { rows, cols, data: vec![T::zero(); checked_len(rows, cols)?] })
+    }
+
+    /// Panic-ва, ако `n * n` препълва `usize`. Без panic -- с `Matrix::try_identity`.
+    ///
+    pub fn identity(n: usize) -> Self {
+        match Matrix::try_identity(n) {
+            Ok(result) => result,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn try_identity(n: usize) -> Result<Self, ShapeError> {
+        let mut result = Matrix::try_zeros(n, n)?;
+        for i in 0..n {
+            result.data[i * n + i] = T::one();
+        }
+        Ok(result)
+    }
+
+    fn zip_with(&self, other: &Matrix<T>, f: impl Fn(T, T) -> T) -> Result<Matrix<T>, LinalgError> {
+        if (self.rows, self.cols) != (other.rows, other.cols) {
+            let error = ShapeError::Mismatch { left: (self.rows, self.cols), right: (other.rows, other.cols) };
+            return Err(error.into());
+        }
+        let data = self.data.iter().zip(other.data.iter()).map(|(&a, &b)| f(a, b)).collect();
+        Ok(Matrix { rows: self.rows, cols: self.cols, data })
+    }
+
+    pub fn checked_add(&self, other: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    pub fn checked_sub(&self, other: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        self.zip_with(other, |a, b| a - b)
+    }
+
+    /// Произведението `self * other`. Броят колони на `self` трябва да е равен на броя редове на
+    /// `other`. Смята се с `mul_blocked`.
+    ///
+    pub fn checked_mul(&self, other: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        self.mul_blocked(other)
+    }
+
+    pub fn scale(&self, k: T) -> Matrix<T> {
+        Matrix { rows: self.rows, cols: self.cols, data: self.data.iter().map(|&x| x * k).collect() }
+    }
+}
+
+/// Операторите panic-ват при несъвместими размери -- за грешка вместо това има `checked_*`.
+///
+macro_rules! matrix_binop {
+    ($imp:ident, $method:ident, $checked:ident) => {
+        impl<T: Num> $imp<&Matrix<T>> for &Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $method(self, other: &Matrix<T>) -> Matrix<T> {
+                match self.$checked(other) {
+                    Ok(result) => result,
+                    Err(e) => panic!("{}", e),
+                }
+            }
+        }
+
+        impl<T: Num> $imp for Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $method(self, other: Matrix<T>) -> Matrix<T> {
+                (&self).$method(&other)
+            }
+        }
+    };
+}
+
+matrix_binop!(Add, add, checked_add);
+matrix_binop!(Sub, sub, checked_sub);
+matrix_binop!(Mul, mul, checked_mul);
+
+impl<T: Num> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, k: T) -> Matrix<T> {
+        self.scale(k)
+    }
+}
+
+impl<T: Num> Mul<T> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, k: T) -> Matrix<T> {
+        self.scale(k)
+    }
+}
+
+/// LU разлагане с частичен избор на водещ елемент: `P * A = L * U`. `L` и `U` са записани в една
+/// матрица -- `U` е горният триъгълник с диагонала, а `L` -- долният, с единици по диагонала.
+///
+#[derive(Debug, Clone)]
+pub struct Lu<T> {
+    lu: Matrix<T>,
+    /// Редът `i` на `P * A` е редът `permutation[i]` на `A`.
+    permutation: Vec<usize>,
+    swaps: usize,
+}
+
+impl<T: Float> Lu<T> {
+    /// Водещ елемент, по-малък по модул от `n * epsilon` пъти най-големия елемент на матрицата, се
+    /// смята за нула.
+    ///
+    pub fn new(a: &Matrix<T>) -> Result<Self, LinalgError> {
+        let n = a.square_size()?;
+        let mut lu = Matrix { rows: n, cols: n, data: a.data.clone() };
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+
+        let largest = a.data.iter().map(|x| x.abs()).fold(T::zero(), |m, x| if x > m { x } else { m });
+        let tolerance = T::from_usize(n) * T::epsilon() * largest;
+
+        for k in 0..n {
+            let pivot = (k..n).fold(k, |best, i| match lu.data[i * n + k].abs() > lu.data[best * n + k].abs() {
+                true => i,
+                false => best,
+            });
+            if lu.data[pivot * n + k].abs() <= tolerance {
+                return Err(LinalgError::Singular);
+            }
+            if pivot != k {
+                for j in 0..n {
+                    lu.data.swap(k * n + j, pivot * n + j);
+                }
+                permutation.swap(k, pivot);
+                swaps += 1;
+            }
+
+            for i in k + 1..n {
+                let factor = lu.data[i * n + k] / lu.data[k * n + k];
+                lu.data[i * n + k] = factor;
+                for j in k + 1..n {
+                    lu.data[i * n + j] = lu.data[i * n + j] - factor * lu.data[k * n + j];
+                }
+            }
+        }
+
+        Ok(Lu { lu, permutation, swaps })
+    }
+
+    pub fn l(&self) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut l = Matrix::identity(n);
+        for i in 0..n {
+            for j in 0..i {
+                l.data[i * n + j] = self.lu.data[i * n + j];
+            }
+        }
+        l
+    }
+
+    pub fn u(&self) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut u = Matrix::zeros(n, n);
+        for i in 0..n {
+            for j in i..n {
+                u.data[i * n + j] = self.lu.data[i * n + j];
+            }
+        }
+        u
+    }
+
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    pub fn determinant(&self) -> T {
+        let n = self.lu.rows;
+        let product = (0..n).fold(T::one(), |acc, i| acc * self.lu.data[i * n + i]);
+        match self.swaps % 2 {
+            0 => product,
+            _ => -product,
+        }
+    }
+
+    /// Решава `A * x = b` с право и обратно заместване.
+    ///
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>, LinalgError> {
+        let n = self.lu.rows;
+        if b.len() != n {
+            return Err(ShapeError::Mismatch { left: (n, n), right: (b.len(), 1) }.into());
+        }
+
+        let mut x: Vec<T> = self.permutation.iter().map(|&i| b[i]).collect();
+        for i in 0..n {
+            for j in 0..i {
+                x[i] = x[i] - self.lu.data[i * n + j] * x[j];
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                x[i] = x[i] - self.lu.data[i * n + j] * x[j];
+            }
+            x[i] = x[i] / self.lu.data[i * n + i];
+        }
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut result = Matrix::zeros(n, n);
+        let mut e = vec![T::zero(); n];
+
+        for j in 0..n {
+            e[j] = T::one();
+            let column = self.solve(&e).unwrap();
+            e[j] = T::zero();
+            for (i, x) in column.into_iter().enumerate() {
+                result.data[i * n + j] = x;
+            }
+        }
+        result
+    }
+}
+
+impl<T> Matrix<T> {
+    fn square_size(&self) -> Result<usize, ShapeError> {
+        match self.rows == self.cols {
+            true => Ok(self.rows),
+            false => Err(ShapeError::NotSquare { rows: self.rows, cols: self.cols }),
+        }
+    }
+}
+
+impl<T: Float> Matrix<T> {
+    pub fn lu(&self) -> Result<Lu<T>, LinalgError> {
+        Lu::new(self)
+    }
+
+    /// Детерминантата. За сингулярна матрица е нула, а не грешка.
+    ///
+    pub fn determinant(&self) -> Result<T, LinalgError> {
+        match Lu::new(self) {
+            Ok(lu) => Ok(lu.determinant()),
+            Err(LinalgError::Singular) => Ok(T::zero()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<T>, LinalgError> {
+        Ok(Lu::new(self)?.inverse())
+    }
+
+    /// Решава системата `self * x = b`.
+    ///
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>, LinalgError> {
+        Lu::new(self)?.solve(b)
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use super::*;
+
+    fn assert_close(actual: &Matrix<f64>, expected: &Matrix<f64>) {
+        assert_eq!((actual.rows, actual.cols), (expected.rows, expected.cols));
+        for (a, e) in actual.by_row().zip(expected.by_row()) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Matrix::new(2, 3, &[1, 2, 3, 4, 5, 6]);
+        let b = Matrix::new(3, 2, &[1, 0, 0, 1, 2, 2]);
+
+        assert_eq!((&a * &b).data, vec![7, 8, 16, 17]);
+        assert_eq!((&a + &a).data, (&a * 2).data);
+        assert_eq!((&a - &a).data, Matrix::zeros(2, 3).data);
+        assert_eq!((&b * &Matrix::identity(2)).data, b.data);
+
+        assert_eq!(a.checked_add(&b).unwrap_err(), ShapeError::Mismatch { left: (2, 3), right: (3, 2) }.into());
+        assert!(a.checked_mul(&a).is_err());
+        assert!(std::panic::catch_unwind(|| &a.clone() * &a.clone()).is_err());
+    }
+
+    #[test]
+    fn test_lu() {
+        let a = Matrix::new(3, 3, &[2.0, 1.0, 1.0, 4.0, -6.0, 0.0, -2.0, 7.0, 2.0]);
+        let lu = a.lu().unwrap();
+
+        let p = lu.permutation();
+        let pa: Vec<f64> = p.iter().flat_map(|&i| a.data[i * 3..(i + 1) * 3].to_vec()).collect();
+        assert_close(&(lu.l() * lu.u()), &Matrix::new(3, 3, &pa));
+        assert_eq!(p[0], 1);
+
+        assert!((a.determinant().unwrap() - -16.0).abs() < 1e-9);
+        assert_close(&(&a * &a.inverse().unwrap()), &Matrix::identity(3));
+
+        let x = a.solve(&[5.0, -2.0, 9.0]).unwrap();
+        assert_close(&Matrix::new(3, 1, &x), &Matrix::new(3, 1, &[1.0, 1.0, 2.0]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let singular = Matrix::new(2, 2, &[1.0, 2.0, 2.0, 4.0]);
+        assert_eq!(singular.determinant(), Ok(0.0));
+        assert_eq!(singular.inverse().unwrap_err(), LinalgError::Singular);
+        assert_eq!(singular.solve(&[1.0, 2.0]).unwrap_err(), LinalgError::Singular);
+
+        let rect = Matrix::new(2, 3, &[1.0; 6]);
+        assert_eq!(rect.determinant().unwrap_err(), ShapeError::NotSquare { rows: 2, cols: 3 }.into());
+        let error = Matrix::<f64>::identity(2).solve(&[1.0]).unwrap_err();
+        assert_eq!(error.to_string(), "incompatible shapes 2x2 and 1x1");
+        assert_eq!(Matrix::<f32>::identity(0).determinant(), Ok(1.0));
+
+        let overflow = ShapeError::Overflow { rows: usize::MAX, cols: 2 };
+        assert_eq!(Matrix::<i32>::try_zeros(usize::MAX, 2).unwrap_err(), overflow);
+        assert_eq!(Matrix::<i32>::try_identity(usize::MAX).unwrap_err().to_string(), format!(
+            "{}x{} elements do not fit in memory", usize::MAX, usize::MAX
+        ));
+        assert_eq!(Matrix::<i32>::try_zeros(2, 0).unwrap().shape(), (2, 0));
+        assert_eq!(Matrix::<i32>::try_identity(2).unwrap(), Matrix::identity(2));
+    }
+}
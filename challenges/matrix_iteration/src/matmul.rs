@@ -0,0 +1,166 @@
+use std::thread;
+
+use crate::shape::checked_len;
+use crate::{LinalgError, Matrix, Num, ShapeError};
+
+/// Страната на квадратните блокове, на които се разбиват матриците. Три блока `f64` по 64x64 се
+/// събират в L2 кеша.
+///
+const BLOCK: usize = 64;
+
+/// Проверява, че `a * b` е дефинирано и че резултатът се събира в паметта -- `a` и `b` може да са
+/// празни, а произведението им не.
+///
+fn check_shapes<T>(a: &Matrix<T>, b: &Matrix<T>) -> Result<(), ShapeError> {
+    if a.cols != b.rows {
+        return Err(ShapeError::Mismatch { left: (a.rows, a.cols), right: (b.rows, b.cols) });
+    }
+    checked_len(a.rows, b.cols).map(|_| ())
+}
+
+/// Добавя `a * b` към `out`, блок по блок. `a` е `out.len() / m` реда по `k` колони, а `b` -- `k`
+/// реда по `m` колони.
+///
+fn blocked_kernel<T: Num>(a: &[T], b: &[T], out: &mut [T], k: usize, m: usize) {
+    let rows = match m {
+        0 => return,
+        m => out.len() / m,
+    };
+
+    for ii in (0..rows).step_by(BLOCK) {
+        for kk in (0..k).step_by(BLOCK) {
+            for jj in (0..m).step_by(BLOCK) {
+                let j_end = usize::min(jj + BLOCK, m);
+
+                for i in ii..usize::min(ii + BLOCK, rows) {
+                    let out_row = &mut out[i * m + jj..i * m + j_end];
+                    for x in kk..usize::min(kk + BLOCK, k) {
+                        let a_ix = a[i * k + x];
+                        for (o, &b_xj) in out_row.iter_mut().zip(&b[x * m + jj..x * m + j_end]) {
+                            *o = *o + a_ix * b_xj;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: Num> Matrix<T> {
+    /// Произведението по определение -- всяка клетка е скаларното произведение на ред от `self` и
+    /// колона от `other`. Бавно е, но е еталонът, с който сравняваме останалите.
+    ///
+    pub fn mul_naive(&self, other: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        check_shapes(self, other)?;
+
+        let mut result = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for j in 0..other.cols {
+                result.data[i * other.cols + j] = (0..self.cols)
+                    .map(|x| self.data[i * self.cols + x] * other.data[x * other.cols + j])
+                    .fold(T::zero(), |sum, p| sum + p);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Произведението, смятано на блокове `BLOCK` x `BLOCK`, така че да се ползват данни, които
+    /// вече са в кеша. Това ползват и `checked_mul`, и `*`.
+    ///
+    pub fn mul_blocked(&self, other: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        check_shapes(self, other)?;
+
+        let mut result = Matrix::zeros(self.rows, other.cols);
+        blocked_kernel(&self.data, &other.data, &mut result.data, self.cols, other.cols);
+        Ok(result)
+    }
+}
+
+impl<T: Num + Send + Sync> Matrix<T> {
+    /// Като `mul_blocked`, но редовете на резултата се делят поравно между `threads` нишки. При
+    /// `threads == 0` се ползват всички ядра.
+    ///
+    pub fn par_mul(&self, other: &Matrix<T>, threads: usize) -> Result<Matrix<T>, LinalgError> {
+        check_shapes(self, other)?;
+
+        // Без елементи в резултата или в сумите няма какво да се дели на нишки
+        let mut result = Matrix::zeros(self.rows, other.cols);
+        if result.data.is_empty() || self.cols == 0 {
+            return Ok(result);
+        }
+
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        let rows_per_thread = self.rows.div_ceil(threads);
+        let (k, m) = (self.cols, other.cols);
+
+        let b = &other.data;
+        thread::scope(|s| {
+            let chunks = result.data.chunks_mut(rows_per_thread * m).zip(self.data.chunks(rows_per_thread * k));
+            for (chunk, a) in chunks {
+                s.spawn(move || blocked_kernel(a, b, chunk, k, m));
+            }
+        });
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use super::*;
+
+    fn pseudo_random(rows: usize, cols: usize, seed: u32) -> Matrix<f64> {
+        let mut state = seed;
+        let data: Vec<f64> = (0..rows * cols)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                (state >> 16) as f64 / 65536.0 - 0.5
+            })
+            .collect();
+        Matrix::new(rows, cols, &data)
+    }
+
+    #[test]
+    fn test_matches_naive() {
+        // Размери, които не са кратни на BLOCK
+        let a = pseudo_random(100, 70, 1);
+        let b = pseudo_random(70, 130, 2);
+        let expected = a.mul_naive(&b).unwrap();
+
+        for result in [a.mul_blocked(&b).unwrap(), a.par_mul(&b, 3).unwrap(), a.par_mul(&b, 0).unwrap()] {
+            assert_eq!((result.rows, result.cols), (100, 130));
+            for (x, y) in result.by_row().zip(expected.by_row()) {
+                assert!((x - y).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_for_integers() {
+        let a = Matrix::new(3, 2, &[1, 2, 3, 4, 5, 6]);
+        let b = Matrix::new(2, 4, &[1, 0, -1, 2, 0, 1, 3, -2]);
+
+        let expected = a.mul_naive(&b).unwrap();
+        assert_eq!(expected.data, vec![1, 2, 5, -2, 3, 4, 9, -2, 5, 6, 13, -2]);
+        assert_eq!(a.mul_blocked(&b).unwrap(), expected);
+        assert_eq!(a.par_mul(&b, 8).unwrap(), expected);
+        assert_eq!(a.par_mul(&a, 2).unwrap_err(), ShapeError::Mismatch { left: (3, 2), right: (3, 2) }.into());
+    }
+
+    #[test]
+    fn test_empty() {
+        let a = Matrix::<i32>::new(2, 0, &[]);
+        let b = Matrix::<i32>::new(0, 3, &[]);
+
+        assert_eq!(a.par_mul(&b, 4).unwrap(), Matrix::zeros(2, 3));
+        assert_eq!(b.mul_blocked(&Matrix::zeros(3, 0)).unwrap().data, Vec::<i32>::new());
+
+        let huge = usize::MAX / 2;
+        let (tall, wide) = (Matrix::<i32>::zeros(huge, 0), Matrix::<i32>::zeros(0, huge));
+        let error = LinalgError::Shape(ShapeError::Overflow { rows: huge, cols: huge });
+        assert_eq!(tall.mul_blocked(&wide).unwrap_err(), error);
+        assert_eq!(tall.par_mul(&wide, 2).unwrap_err(), error);
+    }
+}
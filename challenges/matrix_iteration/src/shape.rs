@@ -0,0 +1,55 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+/// Грешка в размерите -- при конструиране на матрица, при операции между две матрици или при
+/// изглед, който излиза извън матрицата. Размерите са (редове, колони).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShapeError {
+    /// `rows * cols` не се събира в `usize`.
+    Overflow { rows: usize, cols: usize },
+    /// Данните не са точно `rows * cols` на брой.
+    DataLength { rows: usize, cols: usize, len: usize },
+    /// Размерите на операндите не си пасват.
+    Mismatch { left: (usize, usize), right: (usize, usize) },
+    NotSquare { rows: usize, cols: usize },
+    /// Подматрицата с редове `rows` и колони `cols` излиза извън матрица с размери `shape`.
+    OutOfBounds { rows: Range<usize>, cols: Range<usize>, shape: (usize, usize) },
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ShapeError::Overflow { rows, cols } => write!(f, "{}x{} elements do not fit in memory", rows, cols),
+            ShapeError::DataLength { rows, cols, len } => {
+                write!(f, "a {}x{} matrix needs {} elements, got {}", rows, cols, rows * cols, len)
+            }
+            ShapeError::Mismatch { left, right } => {
+                write!(f, "incompatible shapes {}x{} and {}x{}", left.0, left.1, right.0, right.1)
+            }
+            ShapeError::NotSquare { rows, cols } => write!(f, "expected a square matrix, got {}x{}", rows, cols),
+            ShapeError::OutOfBounds { rows, cols, shape } => write!(
+                f,
+                "submatrix [{}..{}, {}..{}] is out of bounds for a {}x{} matrix",
+                rows.start, rows.end, cols.start, cols.end, shape.0, shape.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// Броят елементи на матрица `rows` x `cols`, ако не препълва `usize`.
+///
+pub(crate) fn checked_len(rows: usize, cols: usize) -> Result<usize, ShapeError> {
+    rows.checked_mul(cols).ok_or(ShapeError::Overflow { rows, cols })
+}
+
+/// Дали `len` елемента са точно колкото трябват за матрица `rows` x `cols`.
+///
+pub(crate) fn check_len(rows: usize, cols: usize, len: usize) -> Result<(), ShapeError> {
+    match checked_len(rows, cols)? == len {
+        true => Ok(()),
+        false => Err(ShapeError::DataLength { rows, cols, len }),
+    }
+}
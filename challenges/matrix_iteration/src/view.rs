@@ -0,0 +1,417 @@
+use std::ops::Range;
+use std::slice::IterMut;
+
+use crate::{ColIter, ColIterMut, Matrix, RowIter, RowIterMut, ShapeError};
+
+/// Къде в плоския масив са елементите на един изглед -- елементът `(i, j)` е на индекс
+/// `offset + i * row_stride + j * col_stride`.
+///
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Layout {
+    offset: usize,
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+impl Layout {
+    pub(crate) fn row_major(rows: usize, cols: usize) -> Self {
+        Layout { offset: 0, rows, cols, row_stride: cols, col_stride: 1 }
+    }
+
+    pub(crate) fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub(crate) fn index(&self, row: usize, col: usize) -> usize {
+        self.offset + row * self.row_stride + col * self.col_stride
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<usize> {
+        match row < self.rows && col < self.cols {
+            true => Some(self.index(row, col)),
+            false => None,
+        }
+    }
+
+    fn transpose(self) -> Self {
+        Layout {
+            offset: self.offset,
+            rows: self.cols,
+            cols: self.rows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+        }
+    }
+
+    fn submatrix(self, rows: Range<usize>, cols: Range<usize>) -> Self {
+        match self.try_submatrix(rows, cols) {
+            Ok(layout) => layout,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn try_submatrix(self, rows: Range<usize>, cols: Range<usize>) -> Result<Self, ShapeError> {
+        let fits = |range: &Range<usize>, len| range.start <= range.end && range.end <= len;
+        if !fits(&rows, self.rows) || !fits(&cols, self.cols) {
+            return Err(ShapeError::OutOfBounds { rows, cols, shape: self.shape() });
+        }
+
+        Ok(Layout {
+            offset: self.index(rows.start, cols.start),
+            rows: rows.len(),
+            cols: cols.len(),
+            ..self
+        })
+    }
+
+    /// Ред `i`. Проверяваме `i` преди `i + 1`, за да не препълни при `usize::MAX`.
+    ///
+    fn try_row(self, i: usize) -> Result<Self, ShapeError> {
+        match i < self.rows {
+            true => self.try_submatrix(i..i + 1, 0..self.cols),
+            false => {
+                Err(ShapeError::OutOfBounds { rows: i..i.saturating_add(1), cols: 0..self.cols, shape: self.shape() })
+            }
+        }
+    }
+
+    fn try_col(self, j: usize) -> Result<Self, ShapeError> {
+        match j < self.cols {
+            true => self.try_submatrix(0..self.rows, j..j + 1),
+            false => {
+                Err(ShapeError::OutOfBounds { rows: 0..self.rows, cols: j..j.saturating_add(1), shape: self.shape() })
+            }
+        }
+    }
+}
+
+/// Матрица, която не държи данните си, а гледа в чужд плосък масив. Транспонирането, подматриците,
+/// редовете и колоните също са изгледи и се правят за O(1), без копиране.
+///
+#[derive(Debug)]
+pub struct MatrixView<'a, T> {
+    data: &'a [T],
+    layout: Layout,
+}
+
+impl<T> Clone for MatrixView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MatrixView<'_, T> {}
+
+impl<'a, T> MatrixView<'a, T> {
+    pub(crate) fn new(data: &'a [T], layout: Layout) -> Self {
+        MatrixView { data, layout }
+    }
+
+    /// Брой редове и брой колони.
+    ///
+    pub fn shape(&self) -> (usize, usize) {
+        self.layout.shape()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        let data: &'a [T] = self.data;
+        self.layout.get(row, col).map(|i| &data[i])
+    }
+
+    pub fn by_row(&self) -> RowIter<'a, T> {
+        RowIter { front: 0, back: self.layout.rows * self.layout.cols, view: *self }
+    }
+
+    pub fn by_col(&self) -> ColIter<'a, T> {
+        ColIter { front: 0, back: self.layout.rows * self.layout.cols, view: *self }
+    }
+
+    pub fn transpose(&self) -> MatrixView<'a, T> {
+        MatrixView { data: self.data, layout: self.layout.transpose() }
+    }
+
+    /// Подматрицата с редове `rows` и колони `cols`. Panic-ва, ако излизат извън изгледа.
+    ///
+    pub fn submatrix(&self, rows: Range<usize>, cols: Range<usize>) -> MatrixView<'a, T> {
+        MatrixView { data: self.data, layout: self.layout.submatrix(rows, cols) }
+    }
+
+    /// Като `submatrix`, но вместо да panic-не, връща `ShapeError::OutOfBounds`.
+    ///
+    pub fn try_submatrix(&self, rows: Range<usize>, cols: Range<usize>) -> Result<MatrixView<'a, T>, ShapeError> {
+        Ok(MatrixView { data: self.data, layout: self.layout.try_submatrix(rows, cols)? })
+    }
+
+    /// Ред `i` като изглед 1 x cols. Panic-ва, ако няма такъв ред.
+    ///
+    pub fn row(&self, i: usize) -> MatrixView<'a, T> {
+        match self.try_row(i) {
+            Ok(row) => row,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Като `row`, но вместо да panic-не, връща `ShapeError::OutOfBounds`.
+    ///
+    pub fn try_row(&self, i: usize) -> Result<MatrixView<'a, T>, ShapeError> {
+        Ok(MatrixView { data: self.data, layout: self.layout.try_row(i)? })
+    }
+
+    /// Колона `j` като изглед rows x 1. Panic-ва, ако няма такава колона.
+    ///
+    pub fn col(&self, j: usize) -> MatrixView<'a, T> {
+        match self.try_col(j) {
+            Ok(col) => col,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Като `col`, но вместо да panic-не, връща `ShapeError::OutOfBounds`.
+    ///
+    pub fn try_col(&self, j: usize) -> Result<MatrixView<'a, T>, ShapeError> {
+        Ok(MatrixView { data: self.data, layout: self.layout.try_col(j)? })
+    }
+
+    /// Копие на изгледа като самостоятелна матрица.
+    ///
+    pub fn to_matrix(&self) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        Matrix {
+            rows: self.layout.rows,
+            cols: self.layout.cols,
+            data: self.by_row().cloned().collect(),
+        }
+    }
+}
+
+/// Като `MatrixView`, но с право да променя елементите. Операциите, които стесняват изгледа,
+/// го консумират -- за временен изглед първо се извиква `reborrow`.
+///
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T> {
+    data: &'a mut [T],
+    layout: Layout,
+}
+
+impl<'a, T> MatrixViewMut<'a, T> {
+    pub(crate) fn new(data: &'a mut [T], layout: Layout) -> Self {
+        MatrixViewMut { data, layout }
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        self.layout.shape()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.layout.get(row, col).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.layout.get(row, col).map(|i| &mut self.data[i])
+    }
+
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView { data: self.data, layout: self.layout }
+    }
+
+    pub fn reborrow(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut { data: self.data, layout: self.layout }
+    }
+
+    pub fn transpose(self) -> MatrixViewMut<'a, T> {
+        MatrixViewMut { data: self.data, layout: self.layout.transpose() }
+    }
+
+    pub fn submatrix(self, rows: Range<usize>, cols: Range<usize>) -> MatrixViewMut<'a, T> {
+        MatrixViewMut { data: self.data, layout: self.layout.submatrix(rows, cols) }
+    }
+
+    pub fn try_submatrix(self, rows: Range<usize>, cols: Range<usize>) -> Result<MatrixViewMut<'a, T>, ShapeError> {
+        Ok(MatrixViewMut { data: self.data, layout: self.layout.try_submatrix(rows, cols)? })
+    }
+
+    pub fn row(self, i: usize) -> MatrixViewMut<'a, T> {
+        match self.try_row(i) {
+            Ok(row) => row,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn try_row(self, i: usize) -> Result<MatrixViewMut<'a, T>, ShapeError> {
+        Ok(MatrixViewMut { layout: self.layout.try_row(i)?, data: self.data })
+    }
+
+    pub fn col(self, j: usize) -> MatrixViewMut<'a, T> {
+        match self.try_col(j) {
+            Ok(col) => col,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn try_col(self, j: usize) -> Result<MatrixViewMut<'a, T>, ShapeError> {
+        Ok(MatrixViewMut { layout: self.layout.try_col(j)?, data: self.data })
+    }
+
+    pub fn by_row_mut(self) -> RowIterMut<'a, T> {
+        RowIterMut { lines: self.lines(true) }
+    }
+
+    pub fn by_col_mut(self) -> ColIterMut<'a, T> {
+        ColIterMut { lines: self.lines(false) }
+    }
+
+    /// Разделя изгледа на непрекъснати парчета от масива -- редовете, ако колоните са съседни в
+    /// паметта, или колоните, ако са съседни редовете (както след `transpose`). Обхождането в
+    /// същата посока минава парчетата едно след друго, а в другата -- по един елемент от всяко.
+    ///
+    fn lines(self, row_major: bool) -> Lines<'a, T> {
+        let Layout { offset, rows, cols, row_stride, col_stride } = self.layout;
+        let (mut count, mut len, stride, interleaved) = match col_stride == 1 || cols <= 1 {
+            true => (rows, cols, row_stride, !row_major),
+            false => (cols, rows, col_stride, row_major),
+        };
+        let total = count * len;
+
+        // Съседните парчета, които се обхождат едно след друго, може да се слеят в едно
+        if !interleaved && stride == len {
+            (count, len) = (1, total);
+        }
+
+        let mut lines = Vec::with_capacity(count);
+        if total > 0 {
+            let mut rest = &mut self.data[offset..];
+            for _ in 0..count {
+                let (line, tail) = std::mem::take(&mut rest).split_at_mut(len);
+                lines.push(line.iter_mut());
+                let skip = stride.saturating_sub(len).min(tail.len());
+                rest = &mut tail[skip..];
+            }
+        }
+
+        Lines { lines, len, interleaved, front: 0, back: total }
+    }
+}
+
+/// Обща част на `RowIterMut` и `ColIterMut` -- позиция `pos` в обхождането е следващият елемент на
+/// парче `pos % lines.len()`, ако минаваме по един елемент от всяко, или `pos / len` иначе.
+///
+pub(crate) struct Lines<'a, T> {
+    lines: Vec<IterMut<'a, T>>,
+    len: usize,
+    interleaved: bool,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Lines<'a, T> {
+    fn line(&self, pos: usize) -> usize {
+        match self.interleaved {
+            true => pos % self.lines.len(),
+            false => pos / self.len,
+        }
+    }
+
+    pub(crate) fn next(&mut self) -> Option<&'a mut T> {
+        if self.front == self.back {
+            return None;
+        }
+        let line = self.line(self.front);
+        self.front += 1;
+        self.lines[line].next()
+    }
+
+    pub(crate) fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        let line = self.line(self.back);
+        self.lines[line].next_back()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use crate::{Matrix, ShapeError};
+
+    #[test]
+    fn test_views() {
+        let mat = Matrix::new(3, 4, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+
+        let t = mat.transpose();
+        assert_eq!(t.shape(), (4, 3));
+        assert_eq!(t.by_row().collect::<Vec<_>>(), mat.by_col().collect::<Vec<_>>());
+        assert_eq!(t.by_col().rev().collect::<Vec<_>>(), mat.by_row().rev().collect::<Vec<_>>());
+
+        let sub = mat.submatrix(1..3, 1..3);
+        assert_eq!(sub.by_row().collect::<Vec<_>>(), vec![&6, &7, &10, &11]);
+        assert_eq!(sub.transpose().get(0, 1), Some(&10));
+        assert_eq!(sub.get(2, 0), None);
+
+        assert_eq!(mat.row(1).by_row().collect::<Vec<_>>(), vec![&5, &6, &7, &8]);
+        assert_eq!(mat.col(2).by_row().collect::<Vec<_>>(), vec![&3, &7, &11]);
+        assert_eq!(t.row(2).by_row().collect::<Vec<_>>(), vec![&3, &7, &11]);
+        assert_eq!(sub.transpose().col(0).by_col().len(), 2);
+        assert_eq!(mat.submatrix(3..3, 0..4).by_row().next(), None);
+
+        assert_eq!(t.submatrix(1..3, 0..2).to_matrix().by_row().collect::<Vec<_>>(), vec![&2, &6, &3, &7]);
+    }
+
+    #[test]
+    fn test_views_mut() {
+        let mut mat = Matrix::new(3, 4, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+
+        mat.view_mut().submatrix(0..2, 1..3).by_row_mut().for_each(|x| *x = 0);
+        assert_eq!(mat.by_row().collect::<Vec<_>>(), vec![&1, &0, &0, &4, &5, &0, &0, &8, &9, &10, &11, &12]);
+
+        let mut t = mat.view_mut().transpose();
+        *t.get_mut(3, 0).unwrap() = 40;
+        t.reborrow().row(2).by_row_mut().for_each(|x| *x += 100);
+        let order: Vec<i32> = t.submatrix(0..4, 1..3).by_row_mut().map(|x| *x).collect();
+        assert_eq!(order, vec![5, 9, 0, 10, 100, 111, 8, 12]);
+        assert_eq!(mat.by_row().collect::<Vec<_>>(), vec![&1, &0, &100, &40, &5, &0, &100, &8, &9, &10, &111, &12]);
+
+        let mut it = mat.view_mut().transpose().submatrix(1..4, 0..2).by_col_mut();
+        assert_eq!(it.len(), 6);
+        *it.next().unwrap() = -1;
+        *it.next_back().unwrap() = -2;
+        assert_eq!(it.map(|x| *x).collect::<Vec<_>>(), vec![100, 40, 0, 100]);
+        assert_eq!(mat.by_row().collect::<Vec<_>>(), vec![&1, &-1, &100, &40, &5, &0, &100, &-2, &9, &10, &111, &12]);
+    }
+
+    #[test]
+    fn test_try_row_col() {
+        let mut mat = Matrix::new(2, 3, &[1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(mat.try_row(1).unwrap().by_row().collect::<Vec<_>>(), vec![&4, &5, &6]);
+        assert_eq!(mat.try_col(2).unwrap().by_row().collect::<Vec<_>>(), vec![&3, &6]);
+        assert_eq!(
+            mat.try_row(2).unwrap_err(),
+            ShapeError::OutOfBounds { rows: 2..3, cols: 0..3, shape: (2, 3) }
+        );
+        assert_eq!(
+            mat.try_col(usize::MAX).unwrap_err(),
+            ShapeError::OutOfBounds { rows: 0..2, cols: usize::MAX..usize::MAX, shape: (2, 3) }
+        );
+        assert!(mat.try_row(usize::MAX).is_err());
+        assert!(mat.transpose().try_row(3).is_err());
+
+        mat.view_mut().try_col(0).unwrap().by_row_mut().for_each(|x| *x = 0);
+        assert!(mat.view_mut().try_row(5).is_err());
+        assert_eq!(mat.by_row().collect::<Vec<_>>(), vec![&0, &2, &3, &0, &5, &6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_row_out_of_bounds() {
+        Matrix::new(2, 3, &[1, 2, 3, 4, 5, 6]).row(usize::MAX);
+    }
+}
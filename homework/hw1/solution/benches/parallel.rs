@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+use solution::parallel::{par_counts, par_dna_complement, par_reverse_rna_complement};
+use solution::{counts, dna_complement, reverse_rna_complement};
+
+const LEN: usize = 50_000_000;
+const RUNS: u32 = 5;
+
+fn random_dna(len: usize) -> Vec<char> {
+    let mut state: u32 = 12345;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            ['A', 'C', 'G', 'T'][(state >> 16) as usize % 4]
+        })
+        .collect()
+}
+
+fn measure<T, F: FnMut() -> T>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        std::hint::black_box(f());
+    }
+    start.elapsed() / RUNS
+}
+
+fn report(name: &str, sequential: Duration, parallel: Duration) {
+    println!(
+        "{:<24} sequential {:>10.2?}   parallel {:>10.2?}   speedup {:.2}x",
+        name,
+        sequential,
+        parallel,
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
+
+fn main() {
+    let dna = random_dna(LEN);
+    println!("{} bases, {} runs each, all cores", LEN, RUNS);
+
+    report("counts", measure(|| counts(&dna)), measure(|| par_counts(&dna, 0)));
+    report(
+        "dna_complement",
+        measure(|| dna_complement(&dna)),
+        measure(|| par_dna_complement(&dna, 0)),
+    );
+    report(
+        "reverse_rna_complement",
+        measure(|| reverse_rna_complement(&dna)),
+        measure(|| par_reverse_rna_complement(&dna, 0)),
+    );
+}
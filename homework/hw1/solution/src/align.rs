@@ -0,0 +1,276 @@
+use crate::nucleotide::Nucleotide;
+
+/// Точките за подравняване. Пропуск с дължина `L` струва `gap_open + (L - 1) * gap_extend`, така
+/// че наказанията трябва да са отрицателни.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scoring {
+    pub match_score: i32,
+    pub mismatch: i32,
+    pub gap_open: i32,
+    pub gap_extend: i32,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Scoring {
+            match_score: 2,
+            mismatch: -1,
+            gap_open: -2,
+            gap_extend: -1,
+        }
+    }
+}
+
+/// Резултат от подравняване на `query` спрямо `reference`.
+///
+/// CIGAR низът е в разширения формат -- `=` за съвпадение, `X` за разминаване, `I` за база, която
+/// я има само в `query`, и `D` за база, която я има само в `reference`. В подравнените низове
+/// липсващите бази са `-`. При локално подравняване `reference_start` и `query_start` са
+/// позициите, от които започва подравнената част.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    pub score: i32,
+    pub cigar: String,
+    pub aligned_reference: String,
+    pub aligned_query: String,
+    pub reference_start: usize,
+    pub query_start: usize,
+}
+
+/// Матриците на Gotoh -- завършване със съвпадение/разминаване, с `D` или с `I`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Match,
+    Delete,
+    Insert,
+}
+
+const NEG_INF: i64 = i64::MIN / 4;
+
+/// Най-добрия от кандидатите, като при равенство печели по-ранният.
+///
+fn best(candidates: &[(i64, State)]) -> (i64, State) {
+    let mut result = candidates[0];
+    for &candidate in &candidates[1..] {
+        if candidate.0 > result.0 {
+            result = candidate;
+        }
+    }
+    result
+}
+
+/// Needleman-Wunsch с афинни пропуски (Gotoh) -- подравнява двете последователности изцяло.
+///
+pub fn global_align(reference: &[Nucleotide], query: &[Nucleotide], scoring: &Scoring) -> Alignment {
+    align(reference, query, scoring, false)
+}
+
+/// Smith-Waterman с афинни пропуски -- намира най-добре подравнените части на двете
+/// последователности.
+///
+pub fn local_align(reference: &[Nucleotide], query: &[Nucleotide], scoring: &Scoring) -> Alignment {
+    align(reference, query, scoring, true)
+}
+
+fn align(reference: &[Nucleotide], query: &[Nucleotide], scoring: &Scoring, local: bool) -> Alignment {
+    let (n, m) = (reference.len(), query.len());
+    let (open, extend) = (scoring.gap_open as i64, scoring.gap_extend as i64);
+    let idx = |i: usize, j: usize| i * (m + 1) + j;
+
+    let size = (n + 1) * (m + 1);
+    let mut score_m = vec![NEG_INF; size];
+    let mut score_d = vec![NEG_INF; size];
+    let mut score_i = vec![NEG_INF; size];
+    let mut trace_m = vec![State::Start; size];
+    let mut trace_d = vec![State::Start; size];
+    let mut trace_i = vec![State::Start; size];
+
+    score_m[idx(0, 0)] = 0;
+    if !local {
+        for i in 1..=n {
+            score_d[idx(i, 0)] = open + (i as i64 - 1) * extend;
+            trace_d[idx(i, 0)] = if i == 1 { State::Match } else { State::Delete };
+        }
+        for j in 1..=m {
+            score_i[idx(0, j)] = open + (j as i64 - 1) * extend;
+            trace_i[idx(0, j)] = if j == 1 { State::Match } else { State::Insert };
+        }
+    }
+
+    let mut best_end = (0, State::Start, 0, 0);
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = match reference[i - 1] == query[j - 1] {
+                true => scoring.match_score,
+                false => scoring.mismatch,
+            } as i64;
+
+            // При локално подравняване може и да започнем наново от тази клетка
+            let diagonal = idx(i - 1, j - 1);
+            let candidates = [
+                (score_m[diagonal], State::Match),
+                (score_d[diagonal], State::Delete),
+                (score_i[diagonal], State::Insert),
+                (0, State::Start),
+            ];
+            let count = if local { 4 } else { 3 };
+            let (score, from) = best(&candidates[..count]);
+            score_m[idx(i, j)] = score + substitution;
+            trace_m[idx(i, j)] = from;
+
+            let up = idx(i - 1, j);
+            let (score, from) = best(&[
+                (score_m[up] + open, State::Match),
+                (score_d[up] + extend, State::Delete),
+                (score_i[up] + open, State::Insert),
+            ]);
+            score_d[idx(i, j)] = score;
+            trace_d[idx(i, j)] = from;
+
+            let left = idx(i, j - 1);
+            let (score, from) = best(&[
+                (score_m[left] + open, State::Match),
+                (score_d[left] + open, State::Delete),
+                (score_i[left] + extend, State::Insert),
+            ]);
+            score_i[idx(i, j)] = score;
+            trace_i[idx(i, j)] = from;
+
+            if local && score_m[idx(i, j)] > best_end.0 {
+                best_end = (score_m[idx(i, j)], State::Match, i, j);
+            }
+        }
+    }
+
+    let (score, mut state, mut i, mut j) = match local {
+        true => best_end,
+        false => {
+            let end = idx(n, m);
+            let (score, state) = best(&[
+                (score_m[end], State::Match),
+                (score_d[end], State::Delete),
+                (score_i[end], State::Insert),
+            ]);
+            (score, state, n, m)
+        }
+    };
+
+    let mut ops = Vec::new();
+    let mut aligned_reference = Vec::new();
+    let mut aligned_query = Vec::new();
+    while state != State::Start && (i > 0 || j > 0) {
+        let cell = idx(i, j);
+        match state {
+            State::Match => {
+                ops.push(if reference[i - 1] == query[j - 1] { '=' } else { 'X' });
+                aligned_reference.push(reference[i - 1].to_char());
+                aligned_query.push(query[j - 1].to_char());
+                state = trace_m[cell];
+                i -= 1;
+                j -= 1;
+            }
+            State::Delete => {
+                ops.push('D');
+                aligned_reference.push(reference[i - 1].to_char());
+                aligned_query.push('-');
+                state = trace_d[cell];
+                i -= 1;
+            }
+            State::Insert => {
+                ops.push('I');
+                aligned_reference.push('-');
+                aligned_query.push(query[j - 1].to_char());
+                state = trace_i[cell];
+                j -= 1;
+            }
+            State::Start => unreachable!(),
+        }
+    }
+
+    ops.reverse();
+    Alignment {
+        score: score as i32,
+        cigar: cigar(&ops),
+        aligned_reference: aligned_reference.iter().rev().collect(),
+        aligned_query: aligned_query.iter().rev().collect(),
+        reference_start: i,
+        query_start: j,
+    }
+}
+
+fn cigar(ops: &[char]) -> String {
+    let mut result = String::new();
+    let mut i = 0;
+
+    while i < ops.len() {
+        let run = ops[i..].iter().take_while(|&&op| op == ops[i]).count();
+        result.push_str(&format!("{}{}", run, ops[i]));
+        i += run;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+    use crate::nucleotide::DnaSeq;
+
+    fn seq(s: &str) -> DnaSeq {
+        s.parse().unwrap()
+    }
+
+    fn global(reference: &str, query: &str, scoring: &Scoring) -> Alignment {
+        global_align(seq(reference).as_slice(), seq(query).as_slice(), scoring)
+    }
+
+    #[test]
+    fn test_global_align() {
+        let scoring = Scoring::default();
+
+        let alignment = global("ACGT", "ACGT", &scoring);
+        assert_eq!((alignment.score, alignment.cigar.as_str()), (8, "4="));
+
+        let alignment = global("ACGT", "AGGT", &scoring);
+        assert_eq!((alignment.score, alignment.cigar.as_str()), (5, "1=1X2="));
+
+        let alignment = global("ACGTACGT", "ACGACGT", &scoring);
+        assert_eq!((alignment.score, alignment.cigar.as_str()), (12, "3=1D4="));
+        assert_eq!(alignment.aligned_reference, "ACGTACGT");
+        assert_eq!(alignment.aligned_query, "ACG-ACGT");
+
+        let alignment = global("", "AC", &scoring);
+        assert_eq!((alignment.score, alignment.cigar.as_str()), (-3, "2I"));
+        assert_eq!(alignment.aligned_reference, "--");
+    }
+
+    #[test]
+    fn test_global_align_affine() {
+        let scoring = Scoring { gap_open: -5, gap_extend: -1, ..Scoring::default() };
+
+        let alignment = global("ACGTTTTACG", "ACGACG", &scoring);
+        assert_eq!((alignment.score, alignment.cigar.as_str()), (4, "3=4D3="));
+        assert_eq!(alignment.aligned_query, "ACG----ACG");
+    }
+
+    #[test]
+    fn test_local_align() {
+        let scoring = Scoring::default();
+        let alignment = local_align(
+            seq("TTTTACGTACGTTTTT").as_slice(),
+            seq("GGACGTACGGG").as_slice(),
+            &scoring,
+        );
+
+        assert_eq!((alignment.score, alignment.cigar.as_str()), (14, "7="));
+        assert_eq!(alignment.aligned_reference, "ACGTACG");
+        assert_eq!((alignment.reference_start, alignment.query_start), (4, 2));
+
+        let alignment = local_align(seq("AAAA").as_slice(), seq("CCCC").as_slice(), &scoring);
+        assert_eq!((alignment.score, alignment.cigar.as_str()), (0, ""));
+    }
+}
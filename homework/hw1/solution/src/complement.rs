@@ -0,0 +1,279 @@
+use crate::nucleotide::{Nucleotide, NucleotideError};
+use crate::NucleotideCounter;
+
+/// Една база, която може да бъде комплементирана на място -- `Nucleotide`, `char` или ASCII байт.
+/// За `char` и `u8` валидни са само `A`, `C`, `G` и `T`, както при `dna_complement`.
+///
+pub trait Base: Copy {
+    fn complement_base(self) -> Option<Self>;
+    fn base_char(self) -> char;
+}
+
+impl Base for Nucleotide {
+    fn complement_base(self) -> Option<Self> {
+        Some(self.complement())
+    }
+
+    fn base_char(self) -> char {
+        self.to_char()
+    }
+}
+
+impl Base for char {
+    fn complement_base(self) -> Option<Self> {
+        Nucleotide::from_char(self).map(|n| n.complement().to_char())
+    }
+
+    fn base_char(self) -> char {
+        self
+    }
+}
+
+impl Base for u8 {
+    fn complement_base(self) -> Option<Self> {
+        match self {
+            b'A' => Some(b'T'),
+            b'C' => Some(b'G'),
+            b'G' => Some(b'C'),
+            b'T' => Some(b'A'),
+            _ => None,
+        }
+    }
+
+    fn base_char(self) -> char {
+        self as char
+    }
+}
+
+fn validate<T: Base>(seq: &[T]) -> Result<(), NucleotideError> {
+    match seq.iter().position(|b| b.complement_base().is_none()) {
+        Some(index) => Err(NucleotideError { character: seq[index].base_char(), index }),
+        None => Ok(()),
+    }
+}
+
+/// Заменя всяка база с комплемента ѝ, без да заделя памет. При невалидна база не променя нищо.
+///
+pub fn complement_in_place<T: Base>(seq: &mut [T]) -> Result<(), NucleotideError> {
+    validate(seq)?;
+    for base in seq.iter_mut() {
+        *base = base.complement_base().unwrap();
+    }
+    Ok(())
+}
+
+/// Обръща последователността в обратно-комплементарната ѝ, без да заделя памет. При невалидна
+/// база не променя нищо.
+///
+pub fn reverse_complement_in_place<T: Base>(seq: &mut [T]) -> Result<(), NucleotideError> {
+    validate(seq)?;
+    seq.reverse();
+    for base in seq.iter_mut() {
+        *base = base.complement_base().unwrap();
+    }
+    Ok(())
+}
+
+/// Като `try_counts`, но директно върху ASCII байтове.
+///
+pub fn try_counts_bytes(dna: &[u8]) -> Result<NucleotideCounter, NucleotideError> {
+    let mut result = NucleotideCounter::default();
+
+    for (index, &nucl) in dna.iter().enumerate() {
+        match nucl {
+            b'A' => result.a += 1,
+            b'C' => result.c += 1,
+            b'G' => result.g += 1,
+            b'T' => result.t += 1,
+            _ => return Err(NucleotideError { character: nucl as char, index }),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Елемент на итератор, който `.complement()` и `.rev_complement()` знаят как да комплементират.
+/// `Nucleotide` винаги има комплемент, а за `char` и `u8` получаваме `Result` с индекса на
+/// невалидната база.
+///
+pub trait ComplementItem {
+    type Output;
+
+    fn complement_item(self, index: usize) -> Self::Output;
+}
+
+impl ComplementItem for Nucleotide {
+    type Output = Nucleotide;
+
+    fn complement_item(self, _index: usize) -> Self::Output {
+        self.complement()
+    }
+}
+
+impl ComplementItem for &Nucleotide {
+    type Output = Nucleotide;
+
+    fn complement_item(self, _index: usize) -> Self::Output {
+        self.complement()
+    }
+}
+
+macro_rules! fallible_complement_item {
+    ($base:ty) => {
+        impl ComplementItem for $base {
+            type Output = Result<$base, NucleotideError>;
+
+            fn complement_item(self, index: usize) -> Self::Output {
+                self.complement_base()
+                    .ok_or(NucleotideError { character: self.base_char(), index })
+            }
+        }
+
+        impl ComplementItem for &$base {
+            type Output = Result<$base, NucleotideError>;
+
+            fn complement_item(self, index: usize) -> Self::Output {
+                (*self).complement_item(index)
+            }
+        }
+    };
+}
+
+fallible_complement_item!(char);
+fallible_complement_item!(u8);
+
+/// Адаптерите `.complement()` и `.rev_complement()` за всеки итератор по бази.
+///
+pub trait ComplementExt: Iterator + Sized {
+    /// Комплементът на всяка база, по ред.
+    ///
+    fn complement(self) -> Complement<Self>
+    where
+        Self::Item: ComplementItem,
+    {
+        Complement { iter: self, index: 0 }
+    }
+
+    /// Обратно-комплементарната последователност. Индексите в грешките са тези от входа. Входът
+    /// се чете отзад напред, така че първата грешка е за последната невалидна база.
+    ///
+    fn rev_complement(self) -> RevComplement<Self>
+    where
+        Self: DoubleEndedIterator + ExactSizeIterator,
+        Self::Item: ComplementItem,
+    {
+        RevComplement { iter: self }
+    }
+}
+
+impl<I: Iterator> ComplementExt for I {}
+
+pub struct Complement<I> {
+    iter: I,
+    index: usize,
+}
+
+impl<I> Iterator for Complement<I>
+where
+    I: Iterator,
+    I::Item: ComplementItem,
+{
+    type Item = <I::Item as ComplementItem>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let base = self.iter.next()?;
+        self.index += 1;
+        Some(base.complement_item(self.index - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Complement<I>
+where
+    I: ExactSizeIterator,
+    I::Item: ComplementItem,
+{
+}
+
+pub struct RevComplement<I> {
+    iter: I,
+}
+
+impl<I> Iterator for RevComplement<I>
+where
+    I: DoubleEndedIterator + ExactSizeIterator,
+    I::Item: ComplementItem,
+{
+    type Item = <I::Item as ComplementItem>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let base = self.iter.next_back()?;
+        // След `next_back` оставащите елементи са точно тези преди `base`
+        Some(base.complement_item(self.iter.len()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for RevComplement<I>
+where
+    I: DoubleEndedIterator + ExactSizeIterator,
+    I::Item: ComplementItem,
+{
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+    use crate::nucleotide::DnaSeq;
+    use crate::{dna_complement, try_reverse_rna_complement};
+
+    #[test]
+    fn test_in_place() {
+        let mut bytes = *b"AACGT";
+        reverse_complement_in_place(&mut bytes).unwrap();
+        assert_eq!(&bytes, b"ACGTT");
+        complement_in_place(&mut bytes).unwrap();
+        assert_eq!(&bytes, b"TGCAA");
+
+        let mut chars: Vec<char> = "TGAC".chars().collect();
+        complement_in_place(&mut chars).unwrap();
+        assert_eq!(chars, dna_complement(&['T', 'G', 'A', 'C']));
+
+        let mut invalid = *b"ACNT";
+        assert_eq!(reverse_complement_in_place(&mut invalid), Err(NucleotideError { character: 'N', index: 2 }));
+        assert_eq!(&invalid, b"ACNT");
+    }
+
+    #[test]
+    fn test_counts_bytes() {
+        let counter = try_counts_bytes(b"AAGCT").unwrap();
+        assert_eq!((counter.a, counter.c, counter.g, counter.t), (2, 1, 1, 1));
+        assert_eq!(try_counts_bytes(b"AAa"), Err(NucleotideError { character: 'a', index: 2 }));
+    }
+
+    #[test]
+    fn test_adapters() {
+        let seq: DnaSeq = "AACG".parse().unwrap();
+        let complemented: Vec<Nucleotide> = seq.as_slice().iter().complement().collect();
+        assert_eq!(DnaSeq::new(complemented), seq.complement());
+        let reversed: Vec<Nucleotide> = seq.as_slice().iter().copied().rev_complement().collect();
+        assert_eq!(DnaSeq::new(reversed), seq.reverse_complement());
+
+        let chars: Result<String, _> = "TGAC".chars().complement().collect();
+        assert_eq!(chars.unwrap(), "ACTG");
+        let bytes: Result<Vec<u8>, _> = b"TGAC".iter().rev_complement().collect();
+        assert_eq!(bytes.unwrap(), b"GTCA");
+
+        let input = ['A', 'X', 'C', 'Y'];
+        assert_eq!(input.iter().complement().find_map(Result::err), Some(NucleotideError { character: 'X', index: 1 }));
+        let mut errors = input.iter().rev_complement().filter_map(Result::err);
+        assert_eq!(errors.next(), Some(NucleotideError { character: 'Y', index: 3 }));
+        assert_eq!(errors.last(), try_reverse_rna_complement(&input).err());
+    }
+}
@@ -0,0 +1,289 @@
+use crate::motif::{Motif, Strand};
+use crate::nucleotide::NucleotideError;
+
+/// Какъв край оставя един срез.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overhang {
+    /// Горната нишка е срязана преди долната (напр. EcoRI, `G^AATTC`).
+    FivePrime,
+    /// Горната нишка е срязана след долната (напр. PstI, `CTGCA^G`).
+    ThreePrime,
+    /// Двете нишки са срязани на едно място (напр. EcoRV, `GAT^ATC`).
+    Blunt,
+}
+
+/// Рестрикционен ензим. Отместванията на срезовете са спрямо началото на сайта, в координатите на
+/// горната нишка -- `top` е мястото, където се реже горната нишка, а `bottom` -- долната. Могат да
+/// са и извън сайта (тип IIS ензими), включително отрицателни.
+///
+#[derive(Debug, Clone)]
+pub struct Enzyme {
+    pub name: String,
+    site: Motif,
+    top: isize,
+    bottom: isize,
+}
+
+impl Enzyme {
+    /// Сайтът може да съдържа IUPAC кодове. Например EcoRI е `Enzyme::new("EcoRI", "GAATTC", 1, 5)`.
+    ///
+    pub fn new(name: &str, site: &str, top: isize, bottom: isize) -> Result<Self, NucleotideError> {
+        Ok(Enzyme { name: name.to_string(), site: Motif::new(site)?, top, bottom })
+    }
+
+    pub fn site_len(&self) -> usize {
+        self.site.len()
+    }
+
+    pub fn overhang(&self) -> Overhang {
+        overhang(self.top, self.bottom)
+    }
+
+    /// Срезовете (по горната и долната нишка) за сайт, започващ на `position` по нишката `strand`.
+    /// Сайт по обратната нишка се чете отдясно наляво, така че отместванията се огледат.
+    ///
+    fn cut_at(&self, position: usize, strand: Strand) -> (isize, isize) {
+        let position = position as isize;
+        let end = position + self.site_len() as isize;
+        match strand {
+            Strand::Forward => (position + self.top, position + self.bottom),
+            Strand::Reverse => (end - self.bottom, end - self.top),
+        }
+    }
+}
+
+fn overhang(top: isize, bottom: isize) -> Overhang {
+    match top.cmp(&bottom) {
+        std::cmp::Ordering::Less => Overhang::FivePrime,
+        std::cmp::Ordering::Greater => Overhang::ThreePrime,
+        std::cmp::Ordering::Equal => Overhang::Blunt,
+    }
+}
+
+/// Линейна или кръгова (плазмид) молекула.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topology {
+    Linear,
+    Circular,
+}
+
+/// Един срез. `enzyme` е индексът на ензима в подадения списък, а `top` и `bottom` са позициите
+/// на срезовете по двете нишки, в координатите на горната нишка (срезът е преди базата на тази
+/// позиция).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cut {
+    pub enzyme: usize,
+    pub strand: Strand,
+    pub top: usize,
+    pub bottom: usize,
+}
+
+impl Cut {
+    /// Краят, който оставя срезът. За кръгова молекула срез, минаващ през началото ѝ, може да
+    /// изглежда обърнат.
+    ///
+    pub fn overhang(&self) -> Overhang {
+        overhang(self.top as isize, self.bottom as isize)
+    }
+}
+
+/// Фрагмент след срязване. `start` и `end` са позициите на срезовете по горната нишка, а
+/// `sequence` е горната нишка между тях. За кръгова молекула фрагментът може да минава през
+/// началото ѝ, и тогава `end <= start`. Краищата на молекулата нямат срез.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    pub start: usize,
+    pub end: usize,
+    pub sequence: Vec<char>,
+    pub left: Option<Cut>,
+    pub right: Option<Cut>,
+}
+
+/// Всички срезове на `enzymes` в `sequence`, подредени по позицията по горната нишка. Търсим и по
+/// двете нишки -- при палиндромните сайтове съвпадението е едно, но срезовете пак се смятат и за
+/// двете посоки, в случай че не са симетрични. При кръгова молекула се намират и сайтовете,
+/// минаващи през началото ѝ. Срезове, падащи извън линейна молекула или точно в краищата ѝ, се
+/// пропускат.
+///
+pub fn find_cuts(sequence: &[char], enzymes: &[Enzyme], topology: Topology) -> Vec<Cut> {
+    let n = sequence.len();
+    let mut cuts = Vec::new();
+    if n == 0 {
+        return cuts;
+    }
+
+    // Срез точно в края на линейна молекула не отделя нищо, по която и нишка да е
+    let inside = |x: isize| 0 < x && x < n as isize;
+
+    for (index, enzyme) in enzymes.iter().enumerate() {
+        let text: Vec<char> = match topology {
+            Topology::Linear => sequence.to_vec(),
+            Topology::Circular => {
+                sequence.iter().cycle().take(n + enzyme.site_len().saturating_sub(1)).copied().collect()
+            }
+        };
+
+        for found in enzyme.site.find_iter(&text).filter(|m| m.position < n) {
+            let mut strands = vec![found.strand];
+            if enzyme.site.is_palindromic() {
+                strands.push(Strand::Reverse);
+            }
+
+            for strand in strands {
+                let (top, bottom) = enzyme.cut_at(found.position, strand);
+                let (top, bottom) = match topology {
+                    Topology::Linear if !inside(top) || !inside(bottom) => continue,
+                    Topology::Linear => (top as usize, bottom as usize),
+                    Topology::Circular => (top.rem_euclid(n as isize) as usize, bottom.rem_euclid(n as isize) as usize),
+                };
+
+                let cut = Cut { enzyme: index, strand, top, bottom };
+                if !cuts.iter().any(|c: &Cut| c.enzyme == index && c.top == top && c.bottom == bottom) {
+                    cuts.push(cut);
+                }
+            }
+        }
+    }
+
+    cuts.sort_by_key(|c| (c.top, c.enzyme, c.bottom));
+    cuts
+}
+
+/// Срязва `sequence` с всички `enzymes` едновременно. Ако няколко среза са на едно място по
+/// горната нишка, за фрагментите се взима първият от тях. Несрязана кръгова молекула е един
+/// фрагмент от `0` до `0`.
+///
+pub fn digest(sequence: &[char], enzymes: &[Enzyme], topology: Topology) -> Vec<Fragment> {
+    let n = sequence.len();
+    let mut cuts = find_cuts(sequence, enzymes, topology);
+    cuts.dedup_by_key(|c| c.top);
+
+    if n == 0 {
+        return Vec::new();
+    }
+
+    match topology {
+        Topology::Linear => {
+            let mut bounds: Vec<(usize, Option<Cut>)> = vec![(0, None)];
+            bounds.extend(cuts.into_iter().map(|c| (c.top, Some(c))));
+            bounds.push((n, None));
+
+            bounds
+                .windows(2)
+                .map(|w| Fragment {
+                    start: w[0].0,
+                    end: w[1].0,
+                    sequence: sequence[w[0].0..w[1].0].to_vec(),
+                    left: w[0].1,
+                    right: w[1].1,
+                })
+                .collect()
+        }
+        Topology::Circular if cuts.is_empty() => {
+            vec![Fragment { start: 0, end: 0, sequence: sequence.to_vec(), left: None, right: None }]
+        }
+        Topology::Circular => (0..cuts.len())
+            .map(|i| {
+                let (left, right) = (cuts[i], cuts[(i + 1) % cuts.len()]);
+                let sequence = match left.top < right.top {
+                    true => sequence[left.top..right.top].to_vec(),
+                    false => [&sequence[left.top..], &sequence[..right.top]].concat(),
+                };
+                Fragment { start: left.top, end: right.top, sequence, left: Some(left), right: Some(right) }
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn fragments(sequence: &str, enzymes: &[Enzyme], topology: Topology) -> Vec<(usize, usize, String)> {
+        digest(&chars(sequence), enzymes, topology)
+            .into_iter()
+            .map(|f| (f.start, f.end, f.sequence.into_iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_digest() {
+        let eco_ri = Enzyme::new("EcoRI", "GAATTC", 1, 5).unwrap();
+        assert_eq!(eco_ri.overhang(), Overhang::FivePrime);
+
+        let result = digest(&chars("AAGAATTCTTTGAATTCA"), std::slice::from_ref(&eco_ri), Topology::Linear);
+        assert_eq!(result.len(), 3);
+        assert_eq!((result[0].start, result[0].end), (0, 3));
+        assert_eq!(result[0].right, Some(Cut { enzyme: 0, strand: Strand::Forward, top: 3, bottom: 7 }));
+        assert_eq!(result[1].sequence, chars("AATTCTTTG"));
+        assert_eq!(result[2].right, None);
+
+        assert_eq!(fragments("ACGT", &[eco_ri], Topology::Linear), vec![(0, 4, String::from("ACGT"))]);
+    }
+
+    #[test]
+    fn test_overhangs() {
+        let pst_i = Enzyme::new("PstI", "CTGCAG", 5, 1).unwrap();
+        let eco_rv = Enzyme::new("EcoRV", "GATATC", 3, 3).unwrap();
+        assert_eq!(pst_i.overhang(), Overhang::ThreePrime);
+        assert_eq!(eco_rv.overhang(), Overhang::Blunt);
+
+        let cuts = find_cuts(&chars("TCTGCAGTTGATATCT"), &[pst_i, eco_rv], Topology::Linear);
+        let overhangs: Vec<(usize, Overhang)> = cuts.iter().map(|c| (c.top, c.overhang())).collect();
+        assert_eq!(overhangs, vec![(6, Overhang::ThreePrime), (12, Overhang::Blunt)]);
+    }
+
+    #[test]
+    fn test_reverse_strand_site() {
+        // BsaI: GGTCTC(1/5), реже след сайта
+        let bsa_i = Enzyme::new("BsaI", "GGTCTC", 7, 11).unwrap();
+        let forward = find_cuts(&chars("GGTCTCAACCGGTTTT"), std::slice::from_ref(&bsa_i), Topology::Linear);
+        assert_eq!(forward, vec![Cut { enzyme: 0, strand: Strand::Forward, top: 7, bottom: 11 }]);
+
+        let reverse = find_cuts(&chars("TTTTAACCGAGACC"), std::slice::from_ref(&bsa_i), Topology::Linear);
+        assert_eq!(reverse, vec![Cut { enzyme: 0, strand: Strand::Reverse, top: 3, bottom: 7 }]);
+        assert_eq!(reverse[0].overhang(), Overhang::FivePrime);
+
+        // Срезът би паднал преди началото на молекулата
+        assert_eq!(find_cuts(&chars("AGAGACC"), std::slice::from_ref(&bsa_i), Topology::Linear), vec![]);
+    }
+
+    #[test]
+    fn test_cuts_at_molecule_ends() {
+        // Сайт на позиция 0 -- срезът по долната нишка е точно в края на молекулата, по горната --
+        // вътре в нея. Пропуска се, както и срез в края по горната нишка.
+        let bsa_i = Enzyme::new("BsaI", "GGTCTC", 7, 11).unwrap();
+        assert_eq!(find_cuts(&chars("GGTCTCAACCG"), std::slice::from_ref(&bsa_i), Topology::Linear), vec![]);
+        assert_eq!(fragments("GGTCTCAACCG", &[bsa_i], Topology::Linear).len(), 1);
+
+        let reverse_end = Enzyme::new("Test", "GAATTC", 6, 0).unwrap();
+        assert_eq!(find_cuts(&chars("GAATTCAA"), std::slice::from_ref(&reverse_end), Topology::Linear), vec![]);
+
+        let eco_ri = Enzyme::new("EcoRI", "GAATTC", 1, 5).unwrap();
+        let cuts = find_cuts(&chars("GAATTCAA"), &[eco_ri], Topology::Linear);
+        assert_eq!(cuts, vec![Cut { enzyme: 0, strand: Strand::Forward, top: 1, bottom: 5 }]);
+    }
+
+    #[test]
+    fn test_circular_digest() {
+        let eco_ri = Enzyme::new("EcoRI", "GAATTC", 1, 5).unwrap();
+        let bam_hi = Enzyme::new("BamHI", "GGATCC", 1, 5).unwrap();
+
+        // EcoRI сайтът минава през началото на плазмида
+        let plasmid = "AATTCAAGGATCCAAG";
+        assert_eq!(
+            fragments(plasmid, &[eco_ri.clone(), bam_hi.clone()], Topology::Circular),
+            vec![(0, 8, String::from("AATTCAAG")), (8, 0, String::from("GATCCAAG"))]
+        );
+        assert_eq!(fragments(plasmid, &[bam_hi], Topology::Linear).len(), 2);
+        assert_eq!(fragments("ACGTACGT", &[eco_ri], Topology::Circular), vec![(0, 0, String::from("ACGTACGT"))]);
+    }
+}
@@ -0,0 +1,415 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::io::{BufRead, Lines};
+
+use crate::nucleotide::{DnaSeq, NucleotideError};
+use crate::{try_counts, try_dna_complement, try_reverse_rna_complement, NucleotideCounter};
+
+/// Грешките при четене на FASTA и FASTQ. Всички освен `IoError` носят номера на реда (от 1).
+///
+#[derive(Debug)]
+pub enum ReadError {
+    IoError(std::io::Error),
+    MissingHeader { line_number: usize },
+    EmptyId { line_number: usize },
+    MissingSeparator { line_number: usize },
+    QualityLength { line_number: usize },
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ReadError::IoError(e) => write!(f, "I/O error: {}", e),
+            ReadError::MissingHeader { line_number } => {
+                write!(f, "line {}: expected a record header", line_number)
+            }
+            ReadError::EmptyId { line_number } => {
+                write!(f, "line {}: record header without an id", line_number)
+            }
+            ReadError::MissingSeparator { line_number } => {
+                write!(f, "line {}: expected a '+' separator line", line_number)
+            }
+            ReadError::QualityLength { line_number } => {
+                write!(f, "line {}: quality length does not match sequence length", line_number)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+/// Невалидна база в запис -- грешката с индекса в цялата последователност и номерът на реда във
+/// файла, на който е базата, ако го знаем.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceError {
+    pub error: NucleotideError,
+    pub line_number: Option<usize>,
+}
+
+impl Display for SequenceError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.line_number {
+            Some(line_number) => write!(f, "line {}: {}", line_number, self.error),
+            None => self.error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SequenceError {}
+
+impl From<std::io::Error> for ReadError {
+    fn from(e: std::io::Error) -> Self {
+        ReadError::IoError(e)
+    }
+}
+
+/// Един запис от FASTA или FASTQ файл. `quality` има стойност само за FASTQ.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub id: String,
+    pub description: Option<String>,
+    pub sequence: Vec<char>,
+    pub quality: Option<String>,
+    /// За всеки ред от файла, от който има бази в `sequence` -- индексът на първата от тях и
+    /// номерът на реда.
+    pub line_starts: Vec<(usize, usize)>,
+}
+
+impl Record {
+    /// Запис без бази -- те се добавят с `push_line`.
+    ///
+    pub fn new(id: String, description: Option<String>) -> Self {
+        Record { id, description, sequence: Vec::new(), quality: None, line_starts: Vec::new() }
+    }
+
+    /// Номерът на реда във файла, на който е базата с индекс `index` в `sequence`.
+    ///
+    pub fn line_of(&self, index: usize) -> Option<usize> {
+        let lines = self.line_starts.partition_point(|&(start, _)| start <= index);
+        lines.checked_sub(1).map(|i| self.line_starts[i].1)
+    }
+
+    pub fn locate(&self, error: NucleotideError) -> SequenceError {
+        SequenceError { error, line_number: self.line_of(error.index) }
+    }
+
+    pub fn dna_seq(&self) -> Result<DnaSeq, SequenceError> {
+        DnaSeq::try_from(self.sequence.as_slice()).map_err(|e| self.locate(e))
+    }
+
+    pub fn counts(&self) -> Result<NucleotideCounter, SequenceError> {
+        try_counts(&self.sequence).map_err(|e| self.locate(e))
+    }
+
+    pub fn dna_complement(&self) -> Result<Vec<char>, SequenceError> {
+        try_dna_complement(&self.sequence).map_err(|e| self.locate(e))
+    }
+
+    pub fn reverse_rna_complement(&self) -> Result<Vec<char>, SequenceError> {
+        try_reverse_rna_complement(&self.sequence).map_err(|e| self.locate(e))
+    }
+
+    /// Добавя базите от ред `line_number` на файла към последователността.
+    ///
+    pub fn push_line(&mut self, line: &str, line_number: usize) {
+        let start = self.sequence.len();
+        self.sequence.extend(line.chars().filter(|c| !c.is_whitespace()));
+        if self.sequence.len() > start {
+            self.line_starts.push((start, line_number));
+        }
+    }
+}
+
+/// Разделя заглавния ред (без `>`/`@`) на id и описание.
+///
+fn parse_header(header: &str, line_number: usize) -> Result<(String, Option<String>), ReadError> {
+    let header = header.trim();
+    let (id, description) = match header.find(char::is_whitespace) {
+        Some(i) => (&header[..i], Some(header[i..].trim().to_string())),
+        None => (header, None),
+    };
+
+    if id.is_empty() {
+        return Err(ReadError::EmptyId { line_number });
+    }
+    Ok((id.to_string(), description))
+}
+
+/// Редовете на входа, номерирани от 1.
+///
+struct NumberedLines<B> {
+    lines: Lines<B>,
+    line_number: usize,
+}
+
+impl<B: BufRead> NumberedLines<B> {
+    fn next_line(&mut self) -> Result<Option<(String, usize)>, ReadError> {
+        match self.lines.next() {
+            Some(line) => {
+                self.line_number += 1;
+                Ok(Some((line?, self.line_number)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Чете FASTA записи един по един от нещо, което имплементира `BufRead`.
+///
+/// Последователността може да е на няколко реда. Празните редове и коментарите (редове, които
+/// започват с `;`) се пропускат. След първата грешка итераторът спира.
+///
+pub struct FastaReader<B> {
+    lines: NumberedLines<B>,
+    pending: Option<(String, usize)>,
+    done: bool,
+}
+
+impl<B: BufRead> FastaReader<B> {
+    pub fn new(reader: B) -> Self {
+        FastaReader {
+            lines: NumberedLines { lines: reader.lines(), line_number: 0 },
+            pending: None,
+            done: false,
+        }
+    }
+
+    fn is_skipped(line: &str) -> bool {
+        line.trim().is_empty() || line.starts_with(';')
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, ReadError> {
+        let (header, line_number) = match self.pending.take() {
+            Some(pending) => pending,
+            None => loop {
+                match self.lines.next_line()? {
+                    Some((line, _)) if Self::is_skipped(&line) => continue,
+                    Some(numbered) => break numbered,
+                    None => return Ok(None),
+                }
+            },
+        };
+
+        let header = match header.strip_prefix('>') {
+            Some(header) => header,
+            None => return Err(ReadError::MissingHeader { line_number }),
+        };
+        let (id, description) = parse_header(header, line_number)?;
+
+        let mut record = Record::new(id, description);
+        while let Some((line, line_number)) = self.lines.next_line()? {
+            if line.starts_with('>') {
+                self.pending = Some((line, line_number));
+                break;
+            }
+            if !Self::is_skipped(&line) {
+                record.push_line(&line, line_number);
+            }
+        }
+
+        Ok(Some(record))
+    }
+}
+
+impl<B: BufRead> Iterator for FastaReader<B> {
+    type Item = Result<Record, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_record();
+        if !matches!(result, Ok(Some(_))) {
+            self.done = true;
+        }
+        result.transpose()
+    }
+}
+
+/// Чете FASTQ записи един по един от нещо, което имплементира `BufRead`.
+///
+/// Последователността и качеството може да са на няколко реда -- качеството свършва, когато
+/// дължината му стигне тази на последователността. Празните редове между записите се пропускат.
+/// След първата грешка итераторът спира.
+///
+pub struct FastqReader<B> {
+    lines: NumberedLines<B>,
+    done: bool,
+}
+
+impl<B: BufRead> FastqReader<B> {
+    pub fn new(reader: B) -> Self {
+        FastqReader {
+            lines: NumberedLines { lines: reader.lines(), line_number: 0 },
+            done: false,
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, ReadError> {
+        let (header, line_number) = loop {
+            match self.lines.next_line()? {
+                Some((line, _)) if line.trim().is_empty() => continue,
+                Some(numbered) => break numbered,
+                None => return Ok(None),
+            }
+        };
+
+        let header = match header.strip_prefix('@') {
+            Some(header) => header,
+            None => return Err(ReadError::MissingHeader { line_number }),
+        };
+        let (id, description) = parse_header(header, line_number)?;
+
+        // Ред с `@` преди `+` е заглавието на следващия запис, а не част от последователността
+        let mut record = Record::new(id, description);
+        loop {
+            match self.lines.next_line()? {
+                Some((line, _)) if line.starts_with('+') => break,
+                Some((line, line_number)) if line.starts_with('@') => {
+                    return Err(ReadError::MissingSeparator { line_number })
+                }
+                Some((line, line_number)) => record.push_line(&line, line_number),
+                None => {
+                    return Err(ReadError::MissingSeparator { line_number: self.lines.line_number + 1 })
+                }
+            }
+        }
+
+        let mut quality = String::new();
+        while quality.chars().count() < record.sequence.len() {
+            match self.lines.next_line()? {
+                Some((line, _)) => quality.push_str(line.trim_end()),
+                None => {
+                    return Err(ReadError::QualityLength { line_number: self.lines.line_number + 1 })
+                }
+            }
+        }
+        if quality.chars().count() != record.sequence.len() {
+            return Err(ReadError::QualityLength { line_number: self.lines.line_number });
+        }
+
+        record.quality = Some(quality);
+        Ok(Some(record))
+    }
+}
+
+impl<B: BufRead> Iterator for FastqReader<B> {
+    type Item = Result<Record, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_record();
+        if !matches!(result, Ok(Some(_))) {
+            self.done = true;
+        }
+        result.transpose()
+    }
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    const TEST_FASTA: &str = "
+;comment
+>seq1 first sequence
+ACGT
+TTGA
+
+>seq2
+>seq3 third
+GG
+";
+
+    const TEST_FASTQ: &str = "
+@read1 lane 1
+ACGT
++
+IIII
+@read2
+ACG
+TT
++read2
+II@I
+I
+";
+
+    #[test]
+    fn test_fasta() {
+        let records = FastaReader::new(TEST_FASTA.trim().as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].id, "seq1");
+        assert_eq!(records[0].description.as_deref(), Some("first sequence"));
+        assert_eq!(records[0].sequence, "ACGTTTGA".chars().collect::<Vec<_>>());
+        assert_eq!(records[0].counts().unwrap(), NucleotideCounter { a: 2, c: 1, g: 2, t: 3 });
+        assert_eq!(records[1].sequence, vec![]);
+        assert_eq!(records[2].description.as_deref(), Some("third"));
+        assert_eq!(records[2].dna_complement().unwrap(), vec!['C', 'C']);
+    }
+
+    #[test]
+    fn test_fasta_errors() {
+        let mut reader = FastaReader::new("ACGT\n>seq".as_bytes());
+        assert!(matches!(reader.next(), Some(Err(ReadError::MissingHeader { line_number: 1 }))));
+        assert!(reader.next().is_none());
+
+        let mut reader = FastaReader::new(">seq\nAC\n> \nGG".as_bytes());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(reader.next(), Some(Err(ReadError::EmptyId { line_number: 3 }))));
+
+        let record = FastaReader::new(">seq\nACXT".as_bytes()).next().unwrap().unwrap();
+        let error = NucleotideError { character: 'X', index: 2 };
+        assert_eq!(record.counts(), Err(SequenceError { error, line_number: Some(2) }));
+
+        let record = FastaReader::new(";x\n>seq\nACGT\n\nA CG\nTTNA".as_bytes()).next().unwrap().unwrap();
+        let error = record.dna_complement().unwrap_err();
+        assert_eq!((error.error.index, error.line_number), (9, Some(6)));
+        assert_eq!(error.to_string(), "line 6: Invalid nucleotide 'N' at index 9");
+        assert_eq!((record.line_of(3), record.line_of(4), record.line_of(7)), (Some(3), Some(5), Some(6)));
+    }
+
+    #[test]
+    fn test_fastq() {
+        let records = FastqReader::new(TEST_FASTQ.trim().as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].description.as_deref(), Some("lane 1"));
+        assert_eq!(records[0].quality.as_deref(), Some("IIII"));
+        assert_eq!(records[1].sequence, "ACGTT".chars().collect::<Vec<_>>());
+        assert_eq!(records[1].quality.as_deref(), Some("II@II"));
+        assert_eq!(records[1].reverse_rna_complement().unwrap(), "AACGU".chars().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_fastq_errors() {
+        let mut reader = FastqReader::new(">read\nACGT\n+\nIIII".as_bytes());
+        assert!(matches!(reader.next(), Some(Err(ReadError::MissingHeader { line_number: 1 }))));
+
+        let mut reader = FastqReader::new("@read\nACGT\nIIII".as_bytes());
+        assert!(matches!(reader.next(), Some(Err(ReadError::MissingSeparator { line_number: 4 }))));
+
+        let mut reader = FastqReader::new("@read1\nACGT\n@read2\nACGT\n+\nIIII".as_bytes());
+        assert!(matches!(reader.next(), Some(Err(ReadError::MissingSeparator { line_number: 3 }))));
+        assert!(reader.next().is_none());
+
+        let record = FastqReader::new("@read\nAC\nGU\n+\nIIII".as_bytes()).next().unwrap().unwrap();
+        assert_eq!(record.counts().unwrap_err().line_number, Some(3));
+
+        let mut reader = FastqReader::new("@read\nACGT\n+\nIIIII".as_bytes());
+        assert!(matches!(reader.next(), Some(Err(ReadError::QualityLength { line_number: 4 }))));
+
+        let mut reader = FastqReader::new("@read\nACGT\n+\nII".as_bytes());
+        assert!(matches!(reader.next(), Some(Err(ReadError::QualityLength { line_number: 5 }))));
+    }
+}
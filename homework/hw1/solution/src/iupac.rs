@@ -0,0 +1,136 @@
+use crate::nucleotide::NucleotideError;
+use crate::NucleotideCounter;
+
+/// Комплементът на IUPAC код, със запазване на малка/голяма буква: `A`↔`T`, `C`↔`G`, `R`↔`Y`,
+/// `K`↔`M`, `B`↔`V`, `D`↔`H`, а `S`, `W` и `N` са комплементарни сами на себе си.
+///
+pub fn complement_char(nucl: char) -> Option<char> {
+    let complement = match nucl.to_ascii_uppercase() {
+        'A' => 'T',
+        'T' => 'A',
+        'C' => 'G',
+        'G' => 'C',
+        'R' => 'Y',
+        'Y' => 'R',
+        'K' => 'M',
+        'M' => 'K',
+        'B' => 'V',
+        'V' => 'B',
+        'D' => 'H',
+        'H' => 'D',
+        'S' => 'S',
+        'W' => 'W',
+        'N' => 'N',
+        _ => return None,
+    };
+
+    match nucl.is_ascii_lowercase() {
+        true => Some(complement.to_ascii_lowercase()),
+        false => Some(complement),
+    }
+}
+
+/// Кодовете за нееднозначност, по реда на броячите в `IupacCounter::ambiguous`.
+///
+pub const AMBIGUITY_CODES: [char; 11] = ['N', 'R', 'Y', 'S', 'W', 'K', 'M', 'B', 'D', 'H', 'V'];
+
+/// Резултатът от `counts` -- четирите бази, всеки код за нееднозначност поотделно и
+/// soft-masked базите (с малка буква), които са преброени и в полето на самия код.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IupacCounter {
+    pub bases: NucleotideCounter,
+    /// По един брояч за всеки код в `AMBIGUITY_CODES`, в същия ред.
+    pub ambiguous: [usize; 11],
+    pub masked: usize,
+}
+
+impl IupacCounter {
+    /// Колко пъти се среща IUPAC кодът `code`, без значение от малка/голяма буква. За символи,
+    /// които не са IUPAC кодове, е `None`.
+    ///
+    pub fn get(&self, code: char) -> Option<usize> {
+        match code.to_ascii_uppercase() {
+            'A' => Some(self.bases.a),
+            'C' => Some(self.bases.c),
+            'G' => Some(self.bases.g),
+            'T' => Some(self.bases.t),
+            code => AMBIGUITY_CODES.iter().position(|&c| c == code).map(|i| self.ambiguous[i]),
+        }
+    }
+}
+
+/// Като `counts`, но приема и IUPAC кодовете за нееднозначност, и малки букви (soft-masking).
+///
+pub fn counts(dna: &[char]) -> Result<IupacCounter, NucleotideError> {
+    let mut result = IupacCounter::default();
+
+    for (index, &character) in dna.iter().enumerate() {
+        let code = character.to_ascii_uppercase();
+        match code {
+            'A' => result.bases.a += 1,
+            'C' => result.bases.c += 1,
+            'G' => result.bases.g += 1,
+            'T' => result.bases.t += 1,
+            _ => match AMBIGUITY_CODES.iter().position(|&c| c == code) {
+                Some(i) => result.ambiguous[i] += 1,
+                None => return Err(NucleotideError { character, index }),
+            },
+        }
+        if character.is_ascii_lowercase() {
+            result.masked += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Комплементарната нишка на последователност с IUPAC кодове. Вижте `complement_char`.
+///
+pub fn complement(dna: &[char]) -> Result<Vec<char>, NucleotideError> {
+    dna.iter()
+        .enumerate()
+        .map(|(index, &character)| {
+            complement_char(character).ok_or(NucleotideError { character, index })
+        })
+        .collect()
+}
+
+/// Обратно-комплементарната нишка на последователност с IUPAC кодове. Като при `complement`,
+/// грешката е за първия невалиден символ.
+///
+pub fn reverse_complement(dna: &[char]) -> Result<Vec<char>, NucleotideError> {
+    let mut result = complement(dna)?;
+    result.reverse();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_iupac_counts() {
+        let counter = counts(&chars("ACGTacgtNNnRYkmBDHVSW")).unwrap();
+
+        assert_eq!(counter.bases, NucleotideCounter { a: 2, c: 2, g: 2, t: 2 });
+        assert_eq!(counter.ambiguous, [3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]);
+        assert_eq!((counter.get('N'), counter.get('k'), counter.get('a'), counter.get('U')), (Some(3), Some(1), Some(2), None));
+        assert_eq!(counter.masked, 7);
+
+        assert_eq!(counts(&chars("ACUG")), Err(NucleotideError { character: 'U', index: 2 }));
+    }
+
+    #[test]
+    fn test_iupac_complement() {
+        assert_eq!(complement(&chars("ACGTRYKMBVDHSWN")).unwrap(), chars("TGCAYRMKVBHDSWN"));
+        assert_eq!(complement(&chars("acgTNn")).unwrap(), chars("tgcANn"));
+        assert_eq!(reverse_complement(&chars("AAcgRn")).unwrap(), chars("nYcgTT"));
+        assert_eq!(reverse_complement(&chars("A-C")), Err(NucleotideError { character: '-', index: 1 }));
+        assert_eq!(reverse_complement(&chars("A-C.")), Err(NucleotideError { character: '-', index: 1 }));
+    }
+}
@@ -1,62 +1,125 @@
-#[derive(Debug)]
-pub struct NucleotideCounter {
-    pub a: usize,
-    pub c: usize,
-    pub g: usize,
-    pub t: usize,
-}
-
-pub fn counts(dna: &[char]) -> NucleotideCounter {
-    let mut result = NucleotideCounter {
-        a: 0,
-        c: 0,
-        g: 0,
-        t: 0,
-    };
-
-    for nucl in dna {
-        match nucl {
-            'A' => result.a += 1,
-            'C' => result.c += 1,
-            'G' => result.g += 1,
-            'T' => result.t += 1,
-            _ => panic!("Invalid nucleotide."),
-        }
-    }
-
-    result
-}
-
-pub fn dna_complement(dna: &[char]) -> Vec<char> {
-    let mut result: Vec<char> = Vec::with_capacity(dna.len());
-
-    for n in dna {
-        let nucl: char = match n {
-            'A' => 'T',
-            'C' => 'G',
-            'G' => 'C',
-            'T' => 'A',
-            _ => panic!("Invalid nucleotide."),
-        };
-        result.push(nucl);
-    }
-
-    result
-}
-
-pub fn reverse_rna_complement(dna: &[char]) -> Vec<char> {
-    let mut result: Vec<char> = Vec::with_capacity(dna.len());
-    
-    for n in dna.iter().rev() {
-        let nucl: char = match n {
-            'A' => 'U',
-            'C' => 'G',
-            'G' => 'C',
-            'T' => 'A',
-            _ => panic!("Invalid nucleotide."),
-        };
-        result.push(nucl);
-    }
-
-    result
-}
+pub mod align;
+pub mod complement;
+pub mod digest;
+pub mod fasta;
+pub mod iupac;
+pub mod motif;
+pub mod nucleotide;
+pub mod packed;
+pub mod parallel;
+pub mod stats;
+pub mod translation;
+pub mod variant;
+
+use std::ops::{Add, AddAssign};
+
+use nucleotide::{Nucleotide, NucleotideError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NucleotideCounter {
+    pub a: usize,
+    pub c: usize,
+    pub g: usize,
+    pub t: usize,
+}
+
+impl AddAssign for NucleotideCounter {
+    fn add_assign(&mut self, other: Self) {
+        self.a += other.a;
+        self.c += other.c;
+        self.g += other.g;
+        self.t += other.t;
+    }
+}
+
+impl Add for NucleotideCounter {
+    type Output = NucleotideCounter;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self += other;
+        self
+    }
+}
+
+pub fn counts(dna: &[char]) -> NucleotideCounter {
+    match try_counts(dna) {
+        Ok(result) => result,
+        Err(_) => panic!("Invalid nucleotide."),
+    }
+}
+
+pub fn dna_complement(dna: &[char]) -> Vec<char> {
+    match try_dna_complement(dna) {
+        Ok(result) => result,
+        Err(_) => panic!("Invalid nucleotide."),
+    }
+}
+
+pub fn reverse_rna_complement(dna: &[char]) -> Vec<char> {
+    match try_reverse_rna_complement(dna) {
+        Ok(result) => result,
+        Err(_) => panic!("Invalid nucleotide."),
+    }
+}
+
+/// Като `counts`, но вместо да panic-не, връща първия невалиден символ и индекса му.
+///
+pub fn try_counts(dna: &[char]) -> Result<NucleotideCounter, NucleotideError> {
+    let mut result = NucleotideCounter::default();
+
+    for (i, &nucl) in dna.iter().enumerate() {
+        match Nucleotide::parse(nucl, i)? {
+            Nucleotide::A => result.a += 1,
+            Nucleotide::C => result.c += 1,
+            Nucleotide::G => result.g += 1,
+            Nucleotide::T => result.t += 1,
+        }
+    }
+
+    Ok(result)
+}
+
+/// Като `dna_complement`, но вместо да panic-не, връща първия невалиден символ и индекса му.
+///
+pub fn try_dna_complement(dna: &[char]) -> Result<Vec<char>, NucleotideError> {
+    let mut result: Vec<char> = Vec::with_capacity(dna.len());
+
+    for (i, &nucl) in dna.iter().enumerate() {
+        result.push(Nucleotide::parse(nucl, i)?.complement().to_char());
+    }
+
+    Ok(result)
+}
+
+/// Като `reverse_rna_complement`, но вместо да panic-не, връща първия невалиден символ и индекса
+/// му.
+///
+pub fn try_reverse_rna_complement(dna: &[char]) -> Result<Vec<char>, NucleotideError> {
+    let mut result: Vec<char> = Vec::with_capacity(dna.len());
+
+    for (i, &nucl) in dna.iter().enumerate() {
+        result.push(Nucleotide::parse(nucl, i)?.complement().to_rna_char());
+    }
+
+    result.reverse();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    #[test]
+    fn test_try_variants() {
+        let input: Vec<char> = "TGAC".chars().collect();
+
+        assert_eq!(try_counts(&input), Ok(counts(&input)));
+        assert_eq!(try_dna_complement(&input), Ok(vec!['A', 'C', 'T', 'G']));
+        assert_eq!(try_reverse_rna_complement(&input), Ok(vec!['G', 'U', 'C', 'A']));
+
+        let input: Vec<char> = "AXCN".chars().collect();
+        assert_eq!(try_counts(&input), Err(NucleotideError { character: 'X', index: 1 }));
+        assert_eq!(try_dna_complement(&input), Err(NucleotideError { character: 'X', index: 1 }));
+        assert_eq!(try_reverse_rna_complement(&input), Err(NucleotideError { character: 'X', index: 1 }));
+    }
+}
@@ -1,7 +1,276 @@
-use solution::*;
-
-fn main() {
-    println!("{:?}", counts(&['A', 'C']));
-    println!("{:?}", dna_complement(&['A', 'C']));
-    println!("{:?}", reverse_rna_complement(&['A', 'C']));
-}
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+
+use solution::fasta::{FastaReader, ReadError, Record, SequenceError};
+use solution::iupac::{self, IupacCounter, AMBIGUITY_CODES};
+
+const USAGE: &str = "Usage: solution <count|complement|revcomp|rna> [--iupac] [--format tsv|json] [FILE...]
+
+Reads FASTA or raw sequences from the given files, or from stdin if there are none (or for `-`),
+and writes the result to stdout.
+
+  count       count A, C, G and T in every record (--format tsv|json, tsv by default)
+  complement  complementary DNA strand
+  revcomp     reverse complementary DNA strand
+  rna         reverse complementary RNA strand
+
+Only A, C, G and T are accepted by default. With --iupac, lowercase (soft-masked) bases and the
+IUPAC ambiguity codes N, R, Y, S, W, K, M, B, D, H and V are accepted too: `count` adds a column
+for each code and one for masked bases, and the other commands keep the case of every base.";
+
+const LINE_WIDTH: usize = 60;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Command {
+    Count,
+    Complement,
+    Revcomp,
+    Rna,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Tsv,
+    Json,
+}
+
+struct Args {
+    command: Command,
+    format: Format,
+    iupac: bool,
+    files: Vec<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let command = match args.first().map(String::as_str) {
+        Some("count") => Command::Count,
+        Some("complement") => Command::Complement,
+        Some("revcomp") => Command::Revcomp,
+        Some("rna") => Command::Rna,
+        Some(other) => return Err(format!("unknown command `{}`", other)),
+        None => return Err(String::from("missing command")),
+    };
+
+    let mut format = None;
+    let mut iupac = false;
+    let mut files = Vec::new();
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--format" => match rest.next().map(String::as_str) {
+                Some("tsv") => format = Some(Format::Tsv),
+                Some("json") => format = Some(Format::Json),
+                Some(other) => return Err(format!("unknown format `{}`", other)),
+                None => return Err(String::from("missing value for --format")),
+            },
+            "--iupac" => iupac = true,
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    if format.is_some() && command != Command::Count {
+        return Err(String::from("--format is only supported by `count`"));
+    }
+    if files.is_empty() {
+        files.push(String::from("-"));
+    }
+
+    Ok(Args { command, format: format.unwrap_or(Format::Tsv), iupac, files })
+}
+
+/// Грешка, след която спираме, със съобщение за потребителя.
+///
+struct Failure(String);
+
+fn fail(source: &str, error: impl Display) -> Failure {
+    Failure(format!("{}: {}", source, error))
+}
+
+/// FASTA ли е входът -- гледаме първия непразен символ в буфера, без да го консумираме.
+///
+fn is_fasta<B: BufRead>(reader: &mut B) -> io::Result<bool> {
+    let buf = reader.fill_buf()?;
+    Ok(matches!(buf.iter().find(|b| !b.is_ascii_whitespace()), Some(b'>') | Some(b';')))
+}
+
+/// Суровата последователност от целия вход като един запис с име `source`.
+///
+fn read_raw<B: BufRead>(reader: B, source: &str) -> Result<Record, ReadError> {
+    let mut record = Record::new(source.to_string(), None);
+    for (i, line) in reader.lines().enumerate() {
+        record.push_line(&line?, i + 1);
+    }
+
+    Ok(record)
+}
+
+/// Извиква `process` за всеки запис от `source`, заедно с това дали входът е FASTA.
+///
+fn for_each_record<F>(source: &str, mut process: F) -> Result<(), Failure>
+where
+    F: FnMut(&Record, bool) -> Result<(), Failure>,
+{
+    let mut reader: Box<dyn BufRead> = match source {
+        "-" => Box::new(BufReader::new(io::stdin())),
+        path => Box::new(BufReader::new(File::open(path).map_err(|e| fail(source, e))?)),
+    };
+
+    if !is_fasta(&mut reader).map_err(|e| fail(source, e))? {
+        let record = read_raw(reader, source).map_err(|e| fail(source, e))?;
+        return process(&record, false);
+    }
+
+    for record in FastaReader::new(reader) {
+        process(&record.map_err(|e| fail(source, e))?, true)?;
+    }
+    Ok(())
+}
+
+fn json_string(s: &str) -> String {
+    let mut result = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Колоните на `count` и стойностите им за един запис. С `--iupac` има колона и за всеки код за
+/// нееднозначност, и за soft-masked базите.
+///
+fn count_columns(counter: &IupacCounter, iupac: bool) -> Vec<(String, usize)> {
+    let bases = &counter.bases;
+    let mut columns: Vec<(String, usize)> =
+        vec![("A".into(), bases.a), ("C".into(), bases.c), ("G".into(), bases.g), ("T".into(), bases.t)];
+
+    if iupac {
+        columns.extend(AMBIGUITY_CODES.iter().zip(counter.ambiguous.iter()).map(|(c, &n)| (c.to_string(), n)));
+        columns.push(("masked".into(), counter.masked));
+    }
+    columns
+}
+
+fn write_counts<W: Write>(out: &mut W, rows: &[(String, IupacCounter)], args: &Args) -> io::Result<()> {
+    match args.format {
+        Format::Tsv => {
+            let header = count_columns(&IupacCounter::default(), args.iupac);
+            let names: Vec<&str> = header.iter().map(|(name, _)| name.as_str()).collect();
+            writeln!(out, "id\t{}", names.join("\t"))?;
+            for (id, c) in rows {
+                let values: Vec<String> = count_columns(c, args.iupac).iter().map(|(_, n)| n.to_string()).collect();
+                writeln!(out, "{}\t{}", id, values.join("\t"))?;
+            }
+        }
+        Format::Json => {
+            let objects: Vec<String> = rows
+                .iter()
+                .map(|(id, c)| {
+                    let fields: Vec<String> = count_columns(c, args.iupac)
+                        .iter()
+                        .map(|(name, n)| format!(",\"{}\":{}", name.to_lowercase(), n))
+                        .collect();
+                    format!("{{\"id\":{}{}}}", json_string(id), fields.concat())
+                })
+                .collect();
+            writeln!(out, "[{}]", objects.join(","))?;
+        }
+    }
+    Ok(())
+}
+
+/// Обратно-комплементарната РНК нишка с IUPAC кодове -- `T` става `U`, със запазена малка/голяма
+/// буква.
+///
+fn iupac_reverse_rna_complement(dna: &[char]) -> Result<Vec<char>, solution::nucleotide::NucleotideError> {
+    let result = iupac::reverse_complement(dna)?;
+    Ok(result
+        .into_iter()
+        .map(|c| match c {
+            'T' => 'U',
+            't' => 'u',
+            c => c,
+        })
+        .collect())
+}
+
+fn write_sequence<W: Write>(out: &mut W, record: &Record, sequence: &[char], fasta: bool) -> io::Result<()> {
+    if !fasta {
+        return writeln!(out, "{}", sequence.iter().collect::<String>());
+    }
+
+    match &record.description {
+        Some(description) => writeln!(out, ">{} {}", record.id, description)?,
+        None => writeln!(out, ">{}", record.id)?,
+    }
+    for line in sequence.chunks(LINE_WIDTH) {
+        writeln!(out, "{}", line.iter().collect::<String>())?;
+    }
+    Ok(())
+}
+
+fn run(args: &Args) -> Result<(), Failure> {
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let mut rows = Vec::new();
+
+    for source in &args.files {
+        for_each_record(source, |record, fasta| {
+            let invalid = |e: SequenceError| fail(&format!("{}: {}", source, record.id), e);
+            let seq = &record.sequence;
+
+            let sequence = match (args.command, args.iupac) {
+                (Command::Count, false) => {
+                    let bases = record.counts().map_err(invalid)?;
+                    rows.push((record.id.clone(), IupacCounter { bases, ..Default::default() }));
+                    return Ok(());
+                }
+                (Command::Count, true) => {
+                    rows.push((record.id.clone(), iupac::counts(seq).map_err(|e| invalid(record.locate(e)))?));
+                    return Ok(());
+                }
+                (Command::Complement, false) => record.dna_complement(),
+                (Command::Revcomp, false) => record.dna_seq().map(|seq| seq.reverse_complement().to_chars()),
+                (Command::Rna, false) => record.reverse_rna_complement(),
+                (Command::Complement, true) => iupac::complement(seq).map_err(|e| record.locate(e)),
+                (Command::Revcomp, true) => iupac::reverse_complement(seq).map_err(|e| record.locate(e)),
+                (Command::Rna, true) => iupac_reverse_rna_complement(seq).map_err(|e| record.locate(e)),
+            };
+            let sequence = sequence.map_err(invalid)?;
+
+            write_sequence(&mut out, record, &sequence, fasta).map_err(|e| fail("stdout", e))
+        })?;
+    }
+
+    if args.command == Command::Count {
+        write_counts(&mut out, &rows, args).map_err(|e| fail("stdout", e))?;
+    }
+    out.flush().map_err(|e| fail("stdout", e))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(Failure(message)) = run(&args) {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
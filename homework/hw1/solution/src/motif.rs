@@ -0,0 +1,240 @@
+use crate::iupac;
+use crate::nucleotide::{Nucleotide, NucleotideError};
+
+/// Нишката, на която е намерено съвпадение.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strand {
+    Forward,
+    Reverse,
+}
+
+/// Едно съвпадение на мотив. `position` е началото му в подадената (правата) нишка, независимо от
+/// `strand`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MotifMatch {
+    pub position: usize,
+    pub strand: Strand,
+}
+
+/// Базите, които един IUPAC код допуска, в ред `A`, `C`, `G`, `T`.
+///
+fn allowed(code: char) -> Option<[bool; 4]> {
+    let bases = match code.to_ascii_uppercase() {
+        'A' => "A",
+        'C' => "C",
+        'G' => "G",
+        'T' => "T",
+        'R' => "AG",
+        'Y' => "CT",
+        'S' => "CG",
+        'W' => "AT",
+        'K' => "GT",
+        'M' => "AC",
+        'B' => "CGT",
+        'D' => "AGT",
+        'H' => "ACT",
+        'V' => "ACG",
+        'N' => "ACGT",
+        _ => return None,
+    };
+    let mut result = [false; 4];
+    for base in bases.chars() {
+        result[Nucleotide::from_char(base).unwrap() as usize] = true;
+    }
+    Some(result)
+}
+
+/// Shift-And автомат -- по една битова маска за всяка база, разделена на 64-битови думи, така че
+/// дължината на мотива не е ограничена.
+///
+#[derive(Debug, Clone)]
+struct ShiftAnd {
+    len: usize,
+    masks: [Vec<u64>; 4],
+}
+
+impl ShiftAnd {
+    fn new(pattern: &[char]) -> Result<Self, NucleotideError> {
+        let words = pattern.len().div_ceil(64);
+        let mut masks = [vec![0; words], vec![0; words], vec![0; words], vec![0; words]];
+
+        for (index, &character) in pattern.iter().enumerate() {
+            let bases = allowed(character).ok_or(NucleotideError { character, index })?;
+            for (base, _) in bases.iter().enumerate().filter(|(_, &ok)| ok) {
+                masks[base][index / 64] |= 1 << (index % 64);
+            }
+        }
+
+        Ok(ShiftAnd { len: pattern.len(), masks })
+    }
+
+    fn initial_state(&self) -> Vec<u64> {
+        vec![0; self.len.div_ceil(64)]
+    }
+
+    /// Прочита още една база от текста. Връща дали мотивът завършва на нея.
+    ///
+    fn step(&self, state: &mut [u64], character: char) -> bool {
+        let mask = match Nucleotide::from_char(character.to_ascii_uppercase()) {
+            Some(nucl) => &self.masks[nucl as usize],
+            None => {
+                state.iter_mut().for_each(|word| *word = 0);
+                return false;
+            }
+        };
+
+        let mut carry = 1;
+        for (word, &mask) in state.iter_mut().zip(mask.iter()) {
+            let next_carry = *word >> 63;
+            *word = ((*word << 1) | carry) & mask;
+            carry = next_carry;
+        }
+
+        self.len > 0 && state[(self.len - 1) / 64] & (1 << ((self.len - 1) % 64)) != 0
+    }
+}
+
+/// Мотив за търсене в ДНК, по двете нишки. Може да съдържа IUPAC кодове за нееднозначност, без
+/// значение дали с малки или големи букви. В текста се търсят само `A`, `C`, `G` и `T` (отново без
+/// значение от буквата) -- всичко останало прекъсва съвпадението.
+///
+#[derive(Debug, Clone)]
+pub struct Motif {
+    forward: ShiftAnd,
+    reverse: ShiftAnd,
+    palindromic: bool,
+}
+
+impl Motif {
+    pub fn new(pattern: &str) -> Result<Self, NucleotideError> {
+        let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_uppercase()).collect();
+        let forward = ShiftAnd::new(&pattern)?;
+        let reverse_pattern = iupac::reverse_complement(&pattern)?;
+
+        Ok(Motif {
+            forward,
+            reverse: ShiftAnd::new(&reverse_pattern)?,
+            palindromic: reverse_pattern == pattern,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.forward.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forward.len == 0
+    }
+
+    /// Дали мотивът съвпада с обратно-комплементарния си (като повечето рестрикционни сайтове).
+    /// Такива мотиви се връщат само веднъж, с `Strand::Forward`.
+    ///
+    pub fn is_palindromic(&self) -> bool {
+        self.palindromic
+    }
+
+    /// Всички съвпадения в `text`, подредени по позиция. Текстът се обхожда веднъж, като двете
+    /// нишки се проверяват едновременно. Празен мотив няма съвпадения.
+    ///
+    pub fn find_iter<'a>(&'a self, text: &'a [char]) -> Matches<'a> {
+        Matches {
+            motif: self,
+            text,
+            position: 0,
+            forward_state: self.forward.initial_state(),
+            reverse_state: self.reverse.initial_state(),
+            pending: None,
+        }
+    }
+}
+
+/// Итераторът, който връща `Motif::find_iter`.
+///
+pub struct Matches<'a> {
+    motif: &'a Motif,
+    text: &'a [char],
+    position: usize,
+    forward_state: Vec<u64>,
+    reverse_state: Vec<u64>,
+    pending: Option<MotifMatch>,
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = MotifMatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+
+        while self.position < self.text.len() {
+            let character = self.text[self.position];
+            self.position += 1;
+
+            let forward = self.motif.forward.step(&mut self.forward_state, character);
+            let reverse = self.motif.reverse.step(&mut self.reverse_state, character)
+                && !self.motif.palindromic;
+            let position = self.position.saturating_sub(self.motif.len());
+
+            match (forward, reverse) {
+                (true, true) => {
+                    self.pending = Some(MotifMatch { position, strand: Strand::Reverse });
+                    return Some(MotifMatch { position, strand: Strand::Forward });
+                }
+                (true, false) => return Some(MotifMatch { position, strand: Strand::Forward }),
+                (false, true) => return Some(MotifMatch { position, strand: Strand::Reverse }),
+                (false, false) => {}
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn find(pattern: &str, text: &str) -> Vec<(usize, Strand)> {
+        let motif = Motif::new(pattern).unwrap();
+        motif.find_iter(&chars(text)).map(|m| (m.position, m.strand)).collect()
+    }
+
+    #[test]
+    fn test_exact_motif() {
+        assert_eq!(find("ACC", "ACCGGTACC"), vec![(0, Strand::Forward), (3, Strand::Reverse), (6, Strand::Forward)]);
+        assert_eq!(find("acc", "NNaccNN"), vec![(2, Strand::Forward)]);
+        assert_eq!(find("AAAA", "AAAAA"), vec![(0, Strand::Forward), (1, Strand::Forward)]);
+        assert_eq!(find("", "ACGT"), vec![]);
+    }
+
+    #[test]
+    fn test_degenerate_motif() {
+        assert_eq!(find("GRA", "GAAGGATTC"), vec![(0, Strand::Forward), (3, Strand::Forward), (6, Strand::Reverse)]);
+        assert_eq!(find("ANT", "ACTNAGGT"), vec![(0, Strand::Forward)]);
+        assert!(Motif::new("ACXT").is_err());
+    }
+
+    #[test]
+    fn test_palindromic_motif() {
+        let motif = Motif::new("GAATTC").unwrap();
+        assert!(motif.is_palindromic());
+        assert_eq!(find("GAATTC", "TTGAATTCGAATTC"), vec![(2, Strand::Forward), (8, Strand::Forward)]);
+    }
+
+    #[test]
+    fn test_long_motif() {
+        let pattern = "ACGTTGCA".repeat(10) + "GG";
+        let text = format!("TT{}TT{}", pattern, pattern.replace("GG", "CC"));
+        let reverse: String = iupac::reverse_complement(&chars(&pattern)).unwrap().into_iter().collect();
+        let text = text + &reverse;
+
+        assert_eq!(find(&pattern, &text), vec![(2, Strand::Forward), (2 + 2 * 82 + 2, Strand::Reverse)]);
+    }
+}
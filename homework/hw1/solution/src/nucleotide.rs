@@ -0,0 +1,200 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::NucleotideCounter;
+
+/// Невалиден символ във входа -- самият символ и позицията му.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NucleotideError {
+    pub character: char,
+    pub index: usize,
+}
+
+impl Display for NucleotideError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid nucleotide {:?} at index {}", self.character, self.index)
+    }
+}
+
+impl std::error::Error for NucleotideError {}
+
+/// Една от четирите бази на ДНК.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Nucleotide {
+    A,
+    C,
+    G,
+    T,
+}
+
+impl Nucleotide {
+    /// Като `from_char`, но за символа на позиция `index` във входа -- при невалиден символ
+    /// връща грешка с него и позицията му.
+    ///
+    pub fn parse(character: char, index: usize) -> Result<Self, NucleotideError> {
+        Nucleotide::from_char(character).ok_or(NucleotideError { character, index })
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'A' => Some(Nucleotide::A),
+            'C' => Some(Nucleotide::C),
+            'G' => Some(Nucleotide::G),
+            'T' => Some(Nucleotide::T),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Nucleotide::A => 'A',
+            Nucleotide::C => 'C',
+            Nucleotide::G => 'G',
+            Nucleotide::T => 'T',
+        }
+    }
+
+    /// Същата база в РНК -- `T` става `U`.
+    ///
+    pub fn to_rna_char(self) -> char {
+        match self {
+            Nucleotide::T => 'U',
+            other => other.to_char(),
+        }
+    }
+
+    pub fn complement(self) -> Self {
+        match self {
+            Nucleotide::A => Nucleotide::T,
+            Nucleotide::C => Nucleotide::G,
+            Nucleotide::G => Nucleotide::C,
+            Nucleotide::T => Nucleotide::A,
+        }
+    }
+}
+
+impl From<Nucleotide> for char {
+    fn from(nucl: Nucleotide) -> char {
+        nucl.to_char()
+    }
+}
+
+/// Валидирана ДНК последователност. Веднъж конструирана, операциите върху нея не могат да се
+/// провалят.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct DnaSeq(Vec<Nucleotide>);
+
+impl DnaSeq {
+    pub fn new(nucls: Vec<Nucleotide>) -> Self {
+        DnaSeq(nucls)
+    }
+
+    pub fn as_slice(&self) -> &[Nucleotide] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn counts(&self) -> NucleotideCounter {
+        let mut result = NucleotideCounter::default();
+
+        for nucl in &self.0 {
+            match nucl {
+                Nucleotide::A => result.a += 1,
+                Nucleotide::C => result.c += 1,
+                Nucleotide::G => result.g += 1,
+                Nucleotide::T => result.t += 1,
+            }
+        }
+
+        result
+    }
+
+    pub fn complement(&self) -> DnaSeq {
+        DnaSeq(self.0.iter().map(|n| n.complement()).collect())
+    }
+
+    pub fn reverse_complement(&self) -> DnaSeq {
+        DnaSeq(self.0.iter().rev().map(|n| n.complement()).collect())
+    }
+
+    /// Обратно-комплементарната РНК нишка, като символи.
+    ///
+    pub fn reverse_rna_complement(&self) -> Vec<char> {
+        self.0.iter().rev().map(|n| n.complement().to_rna_char()).collect()
+    }
+
+    pub fn to_chars(&self) -> Vec<char> {
+        self.0.iter().map(|n| n.to_char()).collect()
+    }
+}
+
+impl TryFrom<&[char]> for DnaSeq {
+    type Error = NucleotideError;
+
+    fn try_from(dna: &[char]) -> Result<Self, Self::Error> {
+        dna.iter()
+            .enumerate()
+            .map(|(index, &character)| Nucleotide::parse(character, index))
+            .collect::<Result<Vec<_>, _>>()
+            .map(DnaSeq)
+    }
+}
+
+impl FromStr for DnaSeq {
+    type Err = NucleotideError;
+
+    fn from_str(dna: &str) -> Result<Self, Self::Err> {
+        dna.chars()
+            .enumerate()
+            .map(|(index, character)| Nucleotide::parse(character, index))
+            .collect::<Result<Vec<_>, _>>()
+            .map(DnaSeq)
+    }
+}
+
+impl Display for DnaSeq {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for nucl in &self.0 {
+            write!(f, "{}", nucl.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    #[test]
+    fn test_dna_seq() {
+        let seq: DnaSeq = "AACGT".parse().unwrap();
+
+        assert_eq!(seq.len(), 5);
+        assert_eq!(seq.counts(), NucleotideCounter { a: 2, c: 1, g: 1, t: 1 });
+        assert_eq!(seq.complement().to_string(), "TTGCA");
+        assert_eq!(seq.reverse_complement().to_string(), "ACGTT");
+        assert_eq!(seq.reverse_rna_complement(), vec!['A', 'C', 'G', 'U', 'U']);
+        assert_eq!(DnaSeq::try_from(&['A', 'C', 'G', 'T', 'T', 'A'][..]).unwrap().to_chars(), "ACGTTA".chars().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_dna_seq_errors() {
+        assert_eq!("ACgT".parse::<DnaSeq>(), Err(NucleotideError { character: 'g', index: 2 }));
+        assert_eq!(DnaSeq::try_from(&['X'][..]), Err(NucleotideError { character: 'X', index: 0 }));
+        assert_eq!(
+            NucleotideError { character: 'U', index: 7 }.to_string(),
+            "Invalid nucleotide 'U' at index 7"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::nucleotide::{DnaSeq, Nucleotide, NucleotideError};
+use crate::NucleotideCounter;
+
+const BASES_PER_WORD: usize = 32;
+const LOW_BITS: u64 = 0x5555_5555_5555_5555;
+
+/// Кодиране с по 2 бита на база -- `A`=00, `C`=01, `G`=10, `T`=11. Така комплементът на база е
+/// просто побитово отрицание.
+///
+fn to_bits(nucl: Nucleotide) -> u64 {
+    match nucl {
+        Nucleotide::A => 0b00,
+        Nucleotide::C => 0b01,
+        Nucleotide::G => 0b10,
+        Nucleotide::T => 0b11,
+    }
+}
+
+fn from_bits(bits: u64) -> Nucleotide {
+    match bits & 0b11 {
+        0b00 => Nucleotide::A,
+        0b01 => Nucleotide::C,
+        0b10 => Nucleotide::G,
+        _ => Nucleotide::T,
+    }
+}
+
+/// Обръща реда на 2-битовите групи в една дума.
+///
+fn reverse_pairs(mut word: u64) -> u64 {
+    word = ((word >> 2) & 0x3333_3333_3333_3333) | ((word & 0x3333_3333_3333_3333) << 2);
+    word = ((word >> 4) & 0x0F0F_0F0F_0F0F_0F0F) | ((word & 0x0F0F_0F0F_0F0F_0F0F) << 4);
+    word.swap_bytes()
+}
+
+fn word_count(len: usize) -> usize {
+    len.div_ceil(BASES_PER_WORD)
+}
+
+/// Маска за валидните битове в последната дума на последователност с дължина `len`.
+///
+fn last_word_mask(len: usize) -> u64 {
+    match len % BASES_PER_WORD {
+        0 => u64::MAX,
+        rest => (1 << (2 * rest)) - 1,
+    }
+}
+
+/// ДНК последователност, която пази по 2 бита на база в 64-битови думи. База `i` е в битове
+/// `2 * (i % 32)` и `2 * (i % 32) + 1` на дума `i / 32`. Неизползваните битове в последната дума
+/// винаги са нули.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PackedDna {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl PackedDna {
+    pub fn new() -> Self {
+        PackedDna::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, nucl: Nucleotide) {
+        if self.len.is_multiple_of(BASES_PER_WORD) {
+            self.words.push(0);
+        }
+        let shift = 2 * (self.len % BASES_PER_WORD);
+        *self.words.last_mut().unwrap() |= to_bits(nucl) << shift;
+        self.len += 1;
+    }
+
+    pub fn get(&self, index: usize) -> Option<Nucleotide> {
+        if index >= self.len {
+            return None;
+        }
+        let shift = 2 * (index % BASES_PER_WORD);
+        Some(from_bits(self.words[index / BASES_PER_WORD] >> shift))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Nucleotide> + '_ {
+        (0..self.len).map(move |i| self.get(i).unwrap())
+    }
+
+    /// Броене с popcount: за всяка база старшият бит е в `high`, младшият -- в `low`.
+    ///
+    pub fn counts(&self) -> NucleotideCounter {
+        let mut result = NucleotideCounter::default();
+
+        for (i, &word) in self.words.iter().enumerate() {
+            let valid = match i + 1 == self.words.len() {
+                true => last_word_mask(self.len) & LOW_BITS,
+                false => LOW_BITS,
+            };
+            let high = (word >> 1) & valid;
+            let low = word & valid;
+
+            result.t += (high & low).count_ones() as usize;
+            result.g += (high & !low).count_ones() as usize;
+            result.c += (!high & low).count_ones() as usize;
+            result.a += (!high & !low & valid).count_ones() as usize;
+        }
+
+        result
+    }
+
+    pub fn complement(&self) -> PackedDna {
+        let mut words: Vec<u64> = self.words.iter().map(|w| !w).collect();
+        if let Some(last) = words.last_mut() {
+            *last &= last_word_mask(self.len);
+        }
+        PackedDna { words, len: self.len }
+    }
+
+    /// Обръща думите и групите в тях, след което отмества резултата с броя неизползвани бази в
+    /// последната дума.
+    ///
+    pub fn reverse_complement(&self) -> PackedDna {
+        let reversed: Vec<u64> = self.words.iter().rev().map(|w| reverse_pairs(!w)).collect();
+        let padding = self.words.len() * BASES_PER_WORD - self.len;
+        PackedDna {
+            words: extract(&reversed, padding, self.len),
+            len: self.len,
+        }
+    }
+
+    /// Копие на базите в `range`.
+    ///
+    /// Panic-ва, ако интервалът излиза извън последователността, също като slice.
+    ///
+    pub fn slice(&self, range: Range<usize>) -> PackedDna {
+        assert!(range.start <= range.end && range.end <= self.len, "Slice out of bounds.");
+
+        let len = range.end - range.start;
+        PackedDna {
+            words: extract(&self.words, range.start, len),
+            len,
+        }
+    }
+
+    pub fn to_chars(&self) -> Vec<char> {
+        self.iter().map(Nucleotide::to_char).collect()
+    }
+
+    pub fn to_dna_seq(&self) -> DnaSeq {
+        DnaSeq::new(self.iter().collect())
+    }
+}
+
+/// `len` бази от `words`, започвайки от база `start`, пренаредени от началото на нови думи.
+///
+fn extract(words: &[u64], start: usize, len: usize) -> Vec<u64> {
+    let mut result = Vec::with_capacity(word_count(len));
+
+    for k in 0..word_count(len) {
+        let offset = 2 * (start + k * BASES_PER_WORD);
+        let (index, shift) = (offset / 64, offset % 64);
+
+        let mut word = words[index] >> shift;
+        if shift > 0 && index + 1 < words.len() {
+            word |= words[index + 1] << (64 - shift);
+        }
+        result.push(word);
+    }
+    if let Some(last) = result.last_mut() {
+        *last &= last_word_mask(len);
+    }
+
+    result
+}
+
+impl std::iter::FromIterator<Nucleotide> for PackedDna {
+    fn from_iter<I: IntoIterator<Item = Nucleotide>>(iter: I) -> Self {
+        let mut result = PackedDna::new();
+        for nucl in iter {
+            result.push(nucl);
+        }
+        result
+    }
+}
+
+impl From<&DnaSeq> for PackedDna {
+    fn from(seq: &DnaSeq) -> Self {
+        seq.as_slice().iter().copied().collect()
+    }
+}
+
+impl TryFrom<&[char]> for PackedDna {
+    type Error = NucleotideError;
+
+    fn try_from(dna: &[char]) -> Result<Self, Self::Error> {
+        dna.iter()
+            .enumerate()
+            .map(|(index, &character)| Nucleotide::parse(character, index))
+            .collect()
+    }
+}
+
+impl FromStr for PackedDna {
+    type Err = NucleotideError;
+
+    fn from_str(dna: &str) -> Result<Self, Self::Err> {
+        dna.chars()
+            .enumerate()
+            .map(|(index, character)| Nucleotide::parse(character, index))
+            .collect()
+    }
+}
+
+impl Display for PackedDna {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for nucl in self.iter() {
+            write!(f, "{}", nucl.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    const LONG: &str = "AGCTTTTCATTCTGACTGCAACGGGCAATATGTCTCTGTGTGGATTAAAAAAAGAGTGTCTGATAGCAGC";
+
+    #[test]
+    fn test_packed_roundtrip() {
+        let packed: PackedDna = LONG.parse().unwrap();
+
+        assert_eq!(packed.len(), 70);
+        assert_eq!(packed.to_string(), LONG);
+        assert_eq!(packed.get(2), Some(Nucleotide::C));
+        assert_eq!(packed.get(70), None);
+        assert_eq!(
+            PackedDna::try_from(&['A', 'x'][..]),
+            Err(NucleotideError { character: 'x', index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_packed_counts() {
+        let counter = LONG.parse::<PackedDna>().unwrap().counts();
+        assert_eq!((counter.a, counter.c, counter.g, counter.t), (20, 12, 17, 21));
+
+        assert_eq!(PackedDna::new().counts(), NucleotideCounter::default());
+    }
+
+    #[test]
+    fn test_packed_complement() {
+        let seq: DnaSeq = LONG.parse().unwrap();
+        let packed = PackedDna::from(&seq);
+
+        assert_eq!(packed.complement().to_dna_seq(), seq.complement());
+        assert_eq!(packed.reverse_complement().to_dna_seq(), seq.reverse_complement());
+        assert_eq!(packed.reverse_complement().reverse_complement(), packed);
+
+        for len in [0, 1, 31, 32, 33, 64, 65] {
+            let seq: DnaSeq = LONG.repeat(2)[..len].parse().unwrap();
+            let packed = PackedDna::from(&seq);
+            assert_eq!(packed.reverse_complement().to_dna_seq(), seq.reverse_complement());
+        }
+    }
+
+    #[test]
+    fn test_packed_slice() {
+        let packed: PackedDna = LONG.parse().unwrap();
+
+        assert_eq!(packed.slice(30..67).to_string(), &LONG[30..67]);
+        assert_eq!(packed.slice(0..0), PackedDna::new());
+        assert_eq!(packed.slice(64..70).to_string(), &LONG[64..70]);
+        assert!(std::panic::catch_unwind(|| packed.slice(60..71)).is_err());
+    }
+}
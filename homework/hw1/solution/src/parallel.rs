@@ -0,0 +1,188 @@
+use std::thread;
+
+use crate::nucleotide::{Nucleotide, NucleotideError};
+use crate::{try_counts, NucleotideCounter};
+
+/// Размерът на парчетата, на които делим вход с дължина `len` между `threads` нишки. При
+/// `threads == 0` използваме толкова нишки, колкото ядра има.
+///
+fn chunk_size(len: usize, threads: usize) -> usize {
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    usize::max(1, len.div_ceil(threads))
+}
+
+/// От грешките в отделните парчета избира тази, която би върнала последователната версия --
+/// първия невалиден символ във входа.
+///
+fn pick_error<I>(errors: I) -> Option<NucleotideError>
+where
+    I: Iterator<Item = NucleotideError>,
+{
+    errors.min_by_key(|e| e.index)
+}
+
+/// Като `try_counts`, но входът се дели на парчета, които се броят в отделни нишки. Резултатът,
+/// включително грешката, е същият като на `try_counts`.
+///
+pub fn try_par_counts(dna: &[char], threads: usize) -> Result<NucleotideCounter, NucleotideError> {
+    let size = chunk_size(dna.len(), threads);
+
+    let results: Vec<_> = thread::scope(|s| {
+        let handles: Vec<_> = dna
+            .chunks(size)
+            .enumerate()
+            .map(|(i, chunk)| {
+                s.spawn(move || {
+                    try_counts(chunk).map_err(|e| NucleotideError { index: e.index + i * size, ..e })
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut total = NucleotideCounter::default();
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(counter) => total += counter,
+            Err(e) => errors.push(e),
+        }
+    }
+
+    match pick_error(errors.into_iter()) {
+        Some(e) => Err(e),
+        None => Ok(total),
+    }
+}
+
+/// Пуска `fill` за всяко парче от изхода в отделна нишка. `fill` получава индекса на началото
+/// на парчето и самото парче.
+///
+fn par_fill<F>(len: usize, threads: usize, fill: F) -> Result<Vec<char>, NucleotideError>
+where
+    F: Fn(usize, &mut [char]) -> Result<(), NucleotideError> + Sync,
+{
+    let mut result = vec!['\0'; len];
+    let size = chunk_size(len, threads);
+    let fill = &fill;
+
+    let errors: Vec<_> = thread::scope(|s| {
+        let handles: Vec<_> = result
+            .chunks_mut(size)
+            .enumerate()
+            .map(|(i, chunk)| s.spawn(move || fill(i * size, chunk)))
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().unwrap().err()).collect()
+    });
+
+    match pick_error(errors.into_iter()) {
+        Some(e) => Err(e),
+        None => Ok(result),
+    }
+}
+
+fn parse(dna: &[char], index: usize) -> Result<Nucleotide, NucleotideError> {
+    Nucleotide::parse(dna[index], index)
+}
+
+/// Като `try_dna_complement`, но в `threads` нишки.
+///
+pub fn try_par_dna_complement(dna: &[char], threads: usize) -> Result<Vec<char>, NucleotideError> {
+    par_fill(dna.len(), threads, |start, chunk| {
+        for (i, out) in chunk.iter_mut().enumerate() {
+            *out = parse(dna, start + i)?.complement().to_char();
+        }
+        Ok(())
+    })
+}
+
+/// Като `try_reverse_rna_complement`, но в `threads` нишки.
+///
+pub fn try_par_reverse_rna_complement(
+    dna: &[char],
+    threads: usize,
+) -> Result<Vec<char>, NucleotideError> {
+    // Парчето се попълва отзад напред, така че входът да се чете отпред назад
+    par_fill(dna.len(), threads, |start, chunk| {
+        for (i, out) in chunk.iter_mut().enumerate().rev() {
+            *out = parse(dna, dna.len() - 1 - (start + i))?.complement().to_rna_char();
+        }
+        Ok(())
+    })
+}
+
+pub fn par_counts(dna: &[char], threads: usize) -> NucleotideCounter {
+    match try_par_counts(dna, threads) {
+        Ok(result) => result,
+        Err(_) => panic!("Invalid nucleotide."),
+    }
+}
+
+pub fn par_dna_complement(dna: &[char], threads: usize) -> Vec<char> {
+    match try_par_dna_complement(dna, threads) {
+        Ok(result) => result,
+        Err(_) => panic!("Invalid nucleotide."),
+    }
+}
+
+pub fn par_reverse_rna_complement(dna: &[char], threads: usize) -> Vec<char> {
+    match try_par_reverse_rna_complement(dna, threads) {
+        Ok(result) => result,
+        Err(_) => panic!("Invalid nucleotide."),
+    }
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+    use crate::{counts, dna_complement, reverse_rna_complement, try_dna_complement, try_reverse_rna_complement};
+
+    fn random_dna(len: usize) -> Vec<char> {
+        let mut state: u32 = 12345;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                ['A', 'C', 'G', 'T'][(state >> 16) as usize % 4]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_par_same_as_sequential() {
+        let dna = random_dna(10_007);
+
+        for threads in [0, 1, 3, 8, 64] {
+            assert_eq!(par_counts(&dna, threads), counts(&dna));
+            assert_eq!(par_dna_complement(&dna, threads), dna_complement(&dna));
+            assert_eq!(par_reverse_rna_complement(&dna, threads), reverse_rna_complement(&dna));
+        }
+        assert_eq!(par_counts(&[], 4), NucleotideCounter::default());
+        assert_eq!(par_dna_complement(&[], 4), vec![]);
+    }
+
+    #[test]
+    fn test_par_errors() {
+        let mut dna = random_dna(1000);
+        dna[100] = 'X';
+        dna[900] = 'N';
+
+        assert_eq!(try_par_counts(&dna, 4), try_counts(&dna));
+        assert_eq!(try_par_dna_complement(&dna, 4), try_dna_complement(&dna));
+        assert_eq!(try_par_reverse_rna_complement(&dna, 4), try_reverse_rna_complement(&dna));
+        assert_eq!(try_par_reverse_rna_complement(&dna, 1).unwrap_err().index, 100);
+        assert!(std::panic::catch_unwind(|| par_counts(&dna, 4)).is_err());
+    }
+
+    #[test]
+    fn test_counter_add() {
+        let mut first = counts(&['A', 'C']);
+        let second = counts(&['C', 'G', 'T']);
+
+        assert_eq!(first + second, counts(&['A', 'C', 'C', 'G', 'T']));
+        first += second;
+        assert_eq!(first.c, 2);
+    }
+}
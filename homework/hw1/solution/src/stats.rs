@@ -0,0 +1,332 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display, Formatter};
+
+use crate::nucleotide::Nucleotide;
+use crate::NucleotideCounter;
+
+impl NucleotideCounter {
+    /// Делът на `G` и `C` сред четирите бази. За празен брояч е 0.
+    ///
+    pub fn gc_content(&self) -> f64 {
+        let total = self.a + self.c + self.g + self.t;
+        match total {
+            0 => 0.0,
+            _ => (self.g + self.c) as f64 / total as f64,
+        }
+    }
+
+    /// `(G - C) / (G + C)`, или 0, ако няма нито `G`, нито `C`.
+    ///
+    pub fn gc_skew(&self) -> f64 {
+        match self.g + self.c {
+            0 => 0.0,
+            gc => (self.g as f64 - self.c as f64) / gc as f64,
+        }
+    }
+}
+
+/// GC статистиката на един прозорец, започващ от позиция `start`.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GcWindow {
+    pub start: usize,
+    pub gc_content: f64,
+    pub gc_skew: f64,
+}
+
+/// Итератор по прозорци с дължина `window` през всеки `step` бази. Вижте `gc_windows`.
+///
+pub struct GcWindows<I> {
+    nucls: I,
+    window: usize,
+    step: usize,
+    position: usize,
+    current: VecDeque<Nucleotide>,
+    counter: NucleotideCounter,
+}
+
+/// GC съдържание и GC skew в плъзгащ се прозорец. Последователността се чете само веднъж и в
+/// паметта се пази само текущият прозорец. Прозорците, които не се побират изцяло, се пропускат.
+///
+/// Panic-ва, ако `window` или `step` е 0.
+///
+pub fn gc_windows<I>(nucls: I, window: usize, step: usize) -> GcWindows<I::IntoIter>
+where
+    I: IntoIterator<Item = Nucleotide>,
+{
+    assert!(window > 0 && step > 0, "Window and step must be positive.");
+
+    GcWindows {
+        nucls: nucls.into_iter(),
+        window,
+        step,
+        position: 0,
+        current: VecDeque::with_capacity(window),
+        counter: NucleotideCounter::default(),
+    }
+}
+
+fn count_of(counter: &mut NucleotideCounter, nucl: Nucleotide) -> &mut usize {
+    match nucl {
+        Nucleotide::A => &mut counter.a,
+        Nucleotide::C => &mut counter.c,
+        Nucleotide::G => &mut counter.g,
+        Nucleotide::T => &mut counter.t,
+    }
+}
+
+impl<I: Iterator<Item = Nucleotide>> Iterator for GcWindows<I> {
+    type Item = GcWindow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for nucl in &mut self.nucls {
+            self.current.push_back(nucl);
+            *count_of(&mut self.counter, nucl) += 1;
+            if self.current.len() > self.window {
+                let removed = self.current.pop_front().unwrap();
+                *count_of(&mut self.counter, removed) -= 1;
+            }
+            self.position += 1;
+
+            if self.current.len() == self.window && (self.position - self.window).is_multiple_of(self.step) {
+                return Some(GcWindow {
+                    start: self.position - self.window,
+                    gc_content: self.counter.gc_content(),
+                    gc_skew: self.counter.gc_skew(),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+fn kmer_code(nucl: Nucleotide) -> u64 {
+    match nucl {
+        Nucleotide::A => 0,
+        Nucleotide::C => 1,
+        Nucleotide::G => 2,
+        Nucleotide::T => 3,
+    }
+}
+
+/// k-мер с дължина до 32, пакетиран по 2 бита на база. Първата база е в най-старшите битове,
+/// така че наредбата на k-мерите е лексикографската.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Kmer {
+    k: u8,
+    bits: u64,
+}
+
+impl Kmer {
+    pub fn len(&self) -> usize {
+        self.k as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.k == 0
+    }
+
+    pub fn reverse_complement(&self) -> Kmer {
+        let mut bits = 0;
+        let mut rest = self.bits;
+        for _ in 0..self.k {
+            bits = (bits << 2) | (3 - (rest & 0b11));
+            rest >>= 2;
+        }
+        Kmer { k: self.k, bits }
+    }
+
+    /// По-малкият от k-мера и обратно-комплементарния му.
+    ///
+    pub fn canonical(&self) -> Kmer {
+        Kmer::min(*self, self.reverse_complement())
+    }
+}
+
+impl Display for Kmer {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for i in (0..self.k).rev() {
+            let nucl = match (self.bits >> (2 * i)) & 0b11 {
+                0 => 'A',
+                1 => 'C',
+                2 => 'G',
+                _ => 'T',
+            };
+            write!(f, "{}", nucl)?;
+        }
+        Ok(())
+    }
+}
+
+/// Итератор по всички k-мери на последователност, с обновяване на кода за O(1) на база.
+///
+pub struct Kmers<I> {
+    nucls: I,
+    k: u8,
+    canonical: bool,
+    filled: u8,
+    forward: u64,
+    reverse: u64,
+}
+
+/// Всички k-мери в последователността, по ред. Ако `canonical` е `true`, всеки k-мер се заменя с
+/// каноничния си вид.
+///
+/// Panic-ва, ако `k` не е между 1 и 32.
+///
+pub fn kmers<I>(nucls: I, k: usize, canonical: bool) -> Kmers<I::IntoIter>
+where
+    I: IntoIterator<Item = Nucleotide>,
+{
+    assert!((1..=32).contains(&k), "k must be between 1 and 32.");
+
+    Kmers {
+        nucls: nucls.into_iter(),
+        k: k as u8,
+        canonical,
+        filled: 0,
+        forward: 0,
+        reverse: 0,
+    }
+}
+
+impl<I: Iterator<Item = Nucleotide>> Iterator for Kmers<I> {
+    type Item = Kmer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mask = match self.k {
+            32 => u64::MAX,
+            k => (1 << (2 * k)) - 1,
+        };
+
+        for nucl in &mut self.nucls {
+            let code = kmer_code(nucl);
+            self.forward = ((self.forward << 2) | code) & mask;
+            self.reverse = (self.reverse >> 2) | ((3 - code) << (2 * (self.k - 1)));
+            self.filled = u8::min(self.filled + 1, self.k);
+
+            if self.filled == self.k {
+                let bits = match self.canonical {
+                    true => u64::min(self.forward, self.reverse),
+                    false => self.forward,
+                };
+                return Some(Kmer { k: self.k, bits });
+            }
+        }
+
+        None
+    }
+}
+
+/// Таблица с честотите на каноничните k-мери.
+///
+pub fn kmer_counts<I>(nucls: I, k: usize) -> HashMap<Kmer, usize>
+where
+    I: IntoIterator<Item = Nucleotide>,
+{
+    let mut result = HashMap::new();
+    for kmer in kmers(nucls, k, true) {
+        *result.entry(kmer).or_insert(0) += 1;
+    }
+    result
+}
+
+/// Броят на всяка двойка съседни бази, индексиран с `Nucleotide as usize`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DinucleotideCounts {
+    pub counts: [[usize; 4]; 4],
+}
+
+impl DinucleotideCounts {
+    pub fn get(&self, first: Nucleotide, second: Nucleotide) -> usize {
+        self.counts[first as usize][second as usize]
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Делът на двойката сред всички двойки. За последователност с по-малко от две бази е 0.
+    ///
+    pub fn frequency(&self, first: Nucleotide, second: Nucleotide) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.get(first, second) as f64 / total as f64,
+        }
+    }
+}
+
+pub fn dinucleotide_counts<I>(nucls: I) -> DinucleotideCounts
+where
+    I: IntoIterator<Item = Nucleotide>,
+{
+    let mut result = DinucleotideCounts::default();
+    let mut previous = None;
+
+    for nucl in nucls {
+        if let Some(first) = previous {
+            result.counts[first as usize][nucl as usize] += 1;
+        }
+        previous = Some(nucl);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+    use crate::nucleotide::DnaSeq;
+
+    fn seq(s: &str) -> DnaSeq {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_gc_windows() {
+        let dna = seq("GGCCATAT");
+        let windows = gc_windows(dna.as_slice().iter().copied(), 4, 2).collect::<Vec<_>>();
+
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0], GcWindow { start: 0, gc_content: 1.0, gc_skew: 0.0 });
+        assert_eq!(windows[1], GcWindow { start: 2, gc_content: 0.5, gc_skew: -1.0 });
+        assert_eq!(windows[2], GcWindow { start: 4, gc_content: 0.0, gc_skew: 0.0 });
+
+        assert_eq!(gc_windows(dna.as_slice().iter().copied(), 9, 1).count(), 0);
+        assert_eq!(dna.counts().gc_content(), 0.5);
+    }
+
+    #[test]
+    fn test_kmers() {
+        let dna = seq("ACGTTG");
+
+        let forward = kmers(dna.as_slice().iter().copied(), 3, false).map(|k| k.to_string()).collect::<Vec<_>>();
+        assert_eq!(forward, vec!["ACG", "CGT", "GTT", "TTG"]);
+
+        let canonical = kmers(dna.as_slice().iter().copied(), 3, true).map(|k| k.to_string()).collect::<Vec<_>>();
+        assert_eq!(canonical, vec!["ACG", "ACG", "AAC", "CAA"]);
+
+        let counts = kmer_counts(dna.as_slice().iter().copied(), 3);
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts.values().sum::<usize>(), 4);
+
+        let long = seq(&"ACGT".repeat(10));
+        let kmer = kmers(long.as_slice().iter().copied(), 32, false).next().unwrap();
+        assert_eq!(kmer.to_string(), "ACGT".repeat(8));
+        assert_eq!(kmer.reverse_complement(), kmer);
+    }
+
+    #[test]
+    fn test_dinucleotides() {
+        let counts = dinucleotide_counts(seq("ACGCG").as_slice().iter().copied());
+
+        assert_eq!(counts.total(), 4);
+        assert_eq!(counts.get(Nucleotide::C, Nucleotide::G), 2);
+        assert_eq!(counts.get(Nucleotide::G, Nucleotide::C), 1);
+        assert_eq!(counts.frequency(Nucleotide::A, Nucleotide::C), 0.25);
+        assert_eq!(dinucleotide_counts(seq("A").as_slice().iter().copied()).frequency(Nucleotide::A, Nucleotide::A), 0.0);
+    }
+}
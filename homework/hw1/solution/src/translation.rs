@@ -0,0 +1,450 @@
+use std::convert::TryFrom;
+
+use crate::nucleotide::{DnaSeq, Nucleotide, NucleotideError};
+
+/// Таблица на генетичния код по NCBI. Аминокиселините и стартовите кодони са записани както в
+/// `gc.prt` -- по един символ за всеки от 64-те кодона, в ред `TCAG` за всяка от трите позиции.
+/// Поддържаме всички таблици от `gc.prt`: 1-6, 9-16 и 21-33.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneticCode {
+    pub id: u8,
+    pub name: &'static str,
+    amino_acids: &'static [u8; 64],
+    starts: &'static [u8; 64],
+}
+
+const GENETIC_CODES: [GeneticCode; 27] = [
+    GeneticCode {
+        id: 1,
+        name: "Standard",
+        amino_acids: b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"---M------**--*----M---------------M----------------------------",
+    },
+    GeneticCode {
+        id: 2,
+        name: "Vertebrate Mitochondrial",
+        amino_acids: b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+        starts: b"----------**--------------------MMMM----------**---M------------",
+    },
+    GeneticCode {
+        id: 3,
+        name: "Yeast Mitochondrial",
+        amino_acids: b"FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"----------**----------------------MM---------------M------------",
+    },
+    GeneticCode {
+        id: 4,
+        name: "Mold, Protozoan, and Coelenterate Mitochondrial; Mycoplasma; Spiroplasma",
+        amino_acids: b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"--MM------**-------M------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 5,
+        name: "Invertebrate Mitochondrial",
+        amino_acids: b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+        starts: b"---M------**--------------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 6,
+        name: "Ciliate, Dasycladacean and Hexamita Nuclear",
+        amino_acids: b"FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 9,
+        name: "Echinoderm Mitochondrial; Flatworm Mitochondrial",
+        amino_acids: b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 10,
+        name: "Euplotid Nuclear",
+        amino_acids: b"FFLLSSSSYY**CCCWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 11,
+        name: "Bacterial, Archaeal and Plant Plastid",
+        amino_acids: b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"---M------**--*----M------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 12,
+        name: "Alternative Yeast Nuclear",
+        amino_acids: b"FFLLSSSSYY**CC*WLLLSPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-------------------M---------------M----------------------------",
+    },
+    GeneticCode {
+        id: 13,
+        name: "Ascidian Mitochondrial",
+        amino_acids: b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSGGVVVVAAAADDEEGGGG",
+        starts: b"---M------------------------------MM---------------M------------",
+    },
+    GeneticCode {
+        id: 14,
+        name: "Alternative Flatworm Mitochondrial",
+        amino_acids: b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 15,
+        name: "Blepharisma Macronuclear",
+        amino_acids: b"FFLLSSSSYY*QCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 16,
+        name: "Chlorophycean Mitochondrial",
+        amino_acids: b"FFLLSSSSYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 21,
+        name: "Trematode Mitochondrial",
+        amino_acids: b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 22,
+        name: "Scenedesmus obliquus Mitochondrial",
+        amino_acids: b"FFLLSS*SYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 23,
+        name: "Thraustochytrium Mitochondrial",
+        amino_acids: b"FF*LSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"--------------------------------M--M---------------M------------",
+    },
+    GeneticCode {
+        id: 24,
+        name: "Rhabdopleuridae Mitochondrial",
+        amino_acids: b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        starts: b"---M---------------M---------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 25,
+        name: "Candidate Division SR1 and Gracilibacteria",
+        amino_acids: b"FFLLSSSSYY**CCGWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"---M-------------------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 26,
+        name: "Pachysolen tannophilus Nuclear",
+        amino_acids: b"FFLLSSSSYY**CC*WLLLAPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-------------------M---------------M----------------------------",
+    },
+    GeneticCode {
+        id: 27,
+        name: "Karyorelict Nuclear",
+        amino_acids: b"FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 28,
+        name: "Condylostoma Nuclear",
+        amino_acids: b"FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"----------**--*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 29,
+        name: "Mesodinium Nuclear",
+        amino_acids: b"FFLLSSSSYYYYCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 30,
+        name: "Peritrich Nuclear",
+        amino_acids: b"FFLLSSSSYYEECC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"-----------------------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 31,
+        name: "Blastocrithidia Nuclear",
+        amino_acids: b"FFLLSSSSYYEECCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"----------**-----------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 32,
+        name: "Balanophoraceae Plastid",
+        amino_acids: b"FFLLSSSSYY*WCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: b"---M------*---*----M------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 33,
+        name: "Cephalodiscidae Mitochondrial",
+        amino_acids: b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        starts: b"---M-------*-------M---------------M---------------M------------",
+    },
+];
+
+fn codon_index(codon: &[Nucleotide]) -> usize {
+    codon.iter().fold(0, |index, nucl| {
+        let value = match nucl {
+            Nucleotide::T => 0,
+            Nucleotide::C => 1,
+            Nucleotide::A => 2,
+            Nucleotide::G => 3,
+        };
+        index * 4 + value
+    })
+}
+
+impl GeneticCode {
+    pub fn standard() -> Self {
+        GENETIC_CODES[0]
+    }
+
+    /// Таблицата с номер `id` по NCBI. Номера 7, 8 и 17-20 не се използват.
+    ///
+    pub fn ncbi(id: u8) -> Option<Self> {
+        GENETIC_CODES.iter().find(|code| code.id == id).copied()
+    }
+
+    /// Всички таблици, подредени по номер.
+    ///
+    pub fn all() -> &'static [GeneticCode] {
+        &GENETIC_CODES
+    }
+
+    /// Аминокиселината на кодона като еднобуквен код, `*` за стоп кодон.
+    ///
+    pub fn translate_codon(&self, codon: &[Nucleotide; 3]) -> char {
+        self.amino_acids[codon_index(codon)] as char
+    }
+
+    pub fn is_start(&self, codon: &[Nucleotide; 3]) -> bool {
+        self.starts[codon_index(codon)] == b'M'
+    }
+
+    /// В някои таблици (27, 28, 31) кодонът може да е и аминокиселина, и стоп -- `translate_codon`
+    /// връща аминокиселината, а тук го смятаме за стоп, както е отбелязан в `gc.prt`.
+    ///
+    pub fn is_stop(&self, codon: &[Nucleotide; 3]) -> bool {
+        let index = codon_index(codon);
+        self.amino_acids[index] == b'*' || self.starts[index] == b'*'
+    }
+}
+
+/// Една от шестте рамки на четене -- три по подадената нишка и три по обратно-комплементарната.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Frame {
+    Plus1,
+    Plus2,
+    Plus3,
+    Minus1,
+    Minus2,
+    Minus3,
+}
+
+impl Frame {
+    pub const ALL: [Frame; 6] = [
+        Frame::Plus1,
+        Frame::Plus2,
+        Frame::Plus3,
+        Frame::Minus1,
+        Frame::Minus2,
+        Frame::Minus3,
+    ];
+
+    fn offset(self) -> usize {
+        match self {
+            Frame::Plus1 | Frame::Minus1 => 0,
+            Frame::Plus2 | Frame::Minus2 => 1,
+            Frame::Plus3 | Frame::Minus3 => 2,
+        }
+    }
+
+    fn is_reverse(self) -> bool {
+        matches!(self, Frame::Minus1 | Frame::Minus2 | Frame::Minus3)
+    }
+}
+
+/// Какво правим със стоп кодоните при транслация.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopHandling {
+    /// Стоп кодоните остават в протеина като `*`.
+    Include,
+    /// Транслацията спира на първия стоп кодон.
+    Truncate,
+}
+
+/// Отворена рамка на четене -- от стартов до стоп кодон, включително. `start` и `end` са позиции
+/// в подадената нишка (`end` не е включен), независимо от посоката на рамката. Протеинът е без
+/// стоп кодона и винаги започва с `M`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orf {
+    pub frame: Frame,
+    pub start: usize,
+    pub end: usize,
+    pub protein: String,
+}
+
+/// Приема ДНК или РНК -- `U` се чете като `T`.
+///
+fn parse_nucleic_acid(seq: &[char]) -> Result<DnaSeq, NucleotideError> {
+    seq.iter()
+        .enumerate()
+        .map(|(index, &character)| match character {
+            'U' => Ok(Nucleotide::T),
+            _ => Nucleotide::parse(character, index),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(DnaSeq::new)
+}
+
+/// Транскрипция на ДНК в РНК -- същата нишка, с `U` вместо `T`.
+///
+pub fn transcribe(dna: &[char]) -> Result<Vec<char>, NucleotideError> {
+    let seq = DnaSeq::try_from(dna)?;
+    Ok(seq.as_slice().iter().map(|n| n.to_rna_char()).collect())
+}
+
+fn frame_codons(seq: &DnaSeq, frame: Frame) -> Vec<[Nucleotide; 3]> {
+    let strand = match frame.is_reverse() {
+        true => seq.reverse_complement(),
+        false => seq.clone(),
+    };
+    let nucls = strand.as_slice();
+
+    (frame.offset()..)
+        .step_by(3)
+        .take_while(|i| i + 3 <= nucls.len())
+        .map(|i| [nucls[i], nucls[i + 1], nucls[i + 2]])
+        .collect()
+}
+
+/// Транслация на ДНК или РНК в протеин в рамката `frame`. Непълният кодон накрая се игнорира.
+///
+pub fn translate(
+    seq: &[char],
+    code: &GeneticCode,
+    frame: Frame,
+    stop: StopHandling,
+) -> Result<String, NucleotideError> {
+    let seq = parse_nucleic_acid(seq)?;
+    let mut protein = String::new();
+
+    for codon in frame_codons(&seq, frame) {
+        let amino_acid = code.translate_codon(&codon);
+        if amino_acid == '*' && stop == StopHandling::Truncate {
+            break;
+        }
+        protein.push(amino_acid);
+    }
+
+    Ok(protein)
+}
+
+/// Всички отворени рамки на четене в шестте рамки, с поне `min_len` аминокиселини (без стоп
+/// кодона). ORF-и без стоп кодон до края на последователността не се броят. Вложени ORF-и със
+/// същия стоп кодон не се връщат отделно -- само най-дългият.
+///
+pub fn find_orfs(
+    seq: &[char],
+    code: &GeneticCode,
+    min_len: usize,
+) -> Result<Vec<Orf>, NucleotideError> {
+    let seq = parse_nucleic_acid(seq)?;
+    let mut result = Vec::new();
+
+    for &frame in Frame::ALL.iter() {
+        let codons = frame_codons(&seq, frame);
+        let mut start = None;
+
+        for (i, codon) in codons.iter().enumerate() {
+            if start.is_none() && code.is_start(codon) {
+                start = Some(i);
+            } else if let Some(first) = start {
+                if !code.is_stop(codon) {
+                    continue;
+                }
+                start = None;
+
+                if i - first < min_len {
+                    continue;
+                }
+                let mut protein: String = codons[first..i].iter().map(|c| code.translate_codon(c)).collect();
+                protein.replace_range(..1, "M");
+
+                let (begin, end) = (frame.offset() + 3 * first, frame.offset() + 3 * (i + 1));
+                let (start, end) = match frame.is_reverse() {
+                    true => (seq.len() - end, seq.len() - begin),
+                    false => (begin, end),
+                };
+                result.push(Orf { frame, start, end, protein });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_genetic_codes() {
+        let tga = [Nucleotide::T, Nucleotide::G, Nucleotide::A];
+        let ata = [Nucleotide::A, Nucleotide::T, Nucleotide::A];
+
+        assert_eq!(GeneticCode::standard().translate_codon(&tga), '*');
+        assert_eq!(GeneticCode::ncbi(2).unwrap().translate_codon(&tga), 'W');
+        assert_eq!(GeneticCode::ncbi(2).unwrap().translate_codon(&ata), 'M');
+        assert!(GeneticCode::ncbi(11).unwrap().is_start(&ata));
+        assert!(!GeneticCode::standard().is_start(&ata));
+        assert!(GeneticCode::ncbi(7).is_none());
+
+        let ids: Vec<u8> = GeneticCode::all().iter().map(|code| code.id).collect();
+        let expected: Vec<u8> = (1..=6).chain(9..=16).chain(21..=33).collect();
+        assert_eq!(ids, expected);
+        let aga = [Nucleotide::A, Nucleotide::G, Nucleotide::A];
+        assert_eq!(GeneticCode::ncbi(13).unwrap().translate_codon(&aga), 'G');
+        assert_eq!(GeneticCode::ncbi(33).unwrap().translate_codon(&aga), 'S');
+        let karyorelict = GeneticCode::ncbi(27).unwrap();
+        assert_eq!(karyorelict.translate_codon(&tga), 'W');
+        assert!(karyorelict.is_stop(&tga));
+        assert!(!GeneticCode::ncbi(2).unwrap().is_stop(&tga));
+    }
+
+    #[test]
+    fn test_translate() {
+        let code = GeneticCode::standard();
+        let seq = chars("ATGGCCTAAGGGC");
+
+        assert_eq!(transcribe(&seq).unwrap(), chars("AUGGCCUAAGGGC"));
+        assert_eq!(translate(&seq, &code, Frame::Plus1, StopHandling::Include).unwrap(), "MA*G");
+        assert_eq!(translate(&seq, &code, Frame::Plus1, StopHandling::Truncate).unwrap(), "MA");
+        assert_eq!(translate(&chars("AUGGCC"), &code, Frame::Plus1, StopHandling::Include).unwrap(), "MA");
+        assert_eq!(translate(&seq, &code, Frame::Plus2, StopHandling::Include).unwrap(), "WPKG");
+        // Обратно-комплементарната нишка е GCCCTTAGGCCAT
+        assert_eq!(translate(&seq, &code, Frame::Minus1, StopHandling::Include).unwrap(), "ALRP");
+        assert_eq!(
+            translate(&chars("ATXG"), &code, Frame::Plus1, StopHandling::Include),
+            Err(NucleotideError { character: 'X', index: 2 })
+        );
+    }
+
+    #[test]
+    fn test_find_orfs() {
+        let code = GeneticCode::standard();
+        // ORF в +3 от 2 до 14, и ORF в -1, който на правата нишка е CTAAAGTGCCAT
+        let seq = chars("CCATGAAACCCTAACTAAAGTGCCAT");
+
+        let orfs = find_orfs(&seq, &code, 1).unwrap();
+        assert_eq!(orfs.len(), 2);
+        assert_eq!(orfs[0], Orf { frame: Frame::Plus3, start: 2, end: 14, protein: String::from("MKP") });
+        assert_eq!(orfs[1], Orf { frame: Frame::Minus1, start: 14, end: 26, protein: String::from("MAL") });
+
+        assert_eq!(find_orfs(&seq, &code, 4).unwrap(), vec![]);
+    }
+}
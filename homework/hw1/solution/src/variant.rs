@@ -0,0 +1,416 @@
+use std::fmt::{self, Display, Formatter};
+use std::io::{BufRead, Lines};
+
+use crate::nucleotide::Nucleotide;
+
+/// Една промяна спрямо референцията -- `reference` в нея, започващо от `position` (от 0), се
+/// заменя с `alternate`. Както във VCF, инсерциите и делециите обикновено включват и базата преди
+/// тях, но `reference` може да е и празно (чиста инсерция преди `position`).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub position: usize,
+    pub reference: Vec<char>,
+    pub alternate: Vec<char>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VariantKind {
+    Snp,
+    Insertion,
+    Deletion,
+    /// Всичко останало -- замяна на няколко бази (MNP) или едновременно инсерция и делеция.
+    Complex,
+}
+
+impl Variant {
+    pub fn new(position: usize, reference: &str, alternate: &str) -> Self {
+        Variant { position, reference: reference.chars().collect(), alternate: alternate.chars().collect() }
+    }
+
+    pub fn kind(&self) -> VariantKind {
+        let (r, a) = (self.reference.len(), self.alternate.len());
+        let common = self.reference.iter().zip(self.alternate.iter()).take_while(|(x, y)| x == y).count();
+        match (r, a) {
+            (1, 1) => VariantKind::Snp,
+            (r, a) if r < a && common == r => VariantKind::Insertion,
+            (r, a) if r > a && common == a => VariantKind::Deletion,
+            _ => VariantKind::Complex,
+        }
+    }
+
+    fn end(&self) -> usize {
+        self.position + self.reference.len()
+    }
+}
+
+/// Грешките при прилагане на варианти. `variant` е индексът на варианта в подадения списък.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantError {
+    OutOfBounds { variant: usize },
+    ReferenceMismatch { variant: usize },
+    Overlapping { variant: usize },
+}
+
+impl Display for VariantError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            VariantError::OutOfBounds { variant } => {
+                write!(f, "variant {} is outside the reference", variant)
+            }
+            VariantError::ReferenceMismatch { variant } => {
+                write!(f, "variant {} does not match the reference", variant)
+            }
+            VariantError::Overlapping { variant } => {
+                write!(f, "variant {} overlaps another variant", variant)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VariantError {}
+
+/// Участък, който е или подравнен база за база (`ref_len == alt_len`), или само в едната
+/// последователност (изтрит от референцията или вмъкнат в мутиралата).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    ref_start: usize,
+    alt_start: usize,
+    ref_len: usize,
+    alt_len: usize,
+}
+
+/// Съответствие между позициите в референцията и в мутиралата последователност.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoordinateMap {
+    segments: Vec<Segment>,
+}
+
+impl CoordinateMap {
+    fn push(&mut self, ref_len: usize, alt_len: usize) {
+        let (ref_start, alt_start) = match self.segments.last() {
+            Some(last) => (last.ref_start + last.ref_len, last.alt_start + last.alt_len),
+            None => (0, 0),
+        };
+        if ref_len > 0 || alt_len > 0 {
+            self.segments.push(Segment { ref_start, alt_start, ref_len, alt_len });
+        }
+    }
+
+    /// Позицията в мутиралата последователност, на която е отишла базата от референцията на
+    /// позиция `position`. `None`, ако базата е изтрита или е извън референцията.
+    ///
+    pub fn to_mutated(&self, position: usize) -> Option<usize> {
+        let i = self.segments.partition_point(|s| s.ref_start + s.ref_len <= position);
+        let segment = self.segments.get(i)?;
+        match segment.ref_len == segment.alt_len {
+            true => Some(segment.alt_start + position - segment.ref_start),
+            false => None,
+        }
+    }
+
+    /// Обратното на `to_mutated`. `None`, ако базата е вмъкната или е извън последователността.
+    ///
+    pub fn to_reference(&self, position: usize) -> Option<usize> {
+        let i = self.segments.partition_point(|s| s.alt_start + s.alt_len <= position);
+        let segment = self.segments.get(i)?;
+        match segment.ref_len == segment.alt_len {
+            true => Some(segment.ref_start + position - segment.alt_start),
+            false => None,
+        }
+    }
+}
+
+/// Резултатът от `apply_variants`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mutated {
+    pub sequence: Vec<char>,
+    pub map: CoordinateMap,
+}
+
+/// Прилага `variants` върху `reference`. Редът на вариантите няма значение, но не може да се
+/// припокриват, а `reference` на всеки от тях трябва да съвпада с референцията (без значение от
+/// малки и големи букви). Няколко чисти инсерции на едно място се вмъкват в подадения ред.
+///
+/// В `map` базите, общи за `reference` и `alternate` на варианта (напр. опорната база при
+/// инсерция или делеция, или заменената база при SNP), се съпоставят една на друга.
+///
+pub fn apply_variants(reference: &[char], variants: &[Variant]) -> Result<Mutated, VariantError> {
+    let mut order: Vec<usize> = (0..variants.len()).collect();
+    order.sort_by_key(|&i| (variants[i].position, !variants[i].reference.is_empty()));
+
+    let mut sequence = Vec::with_capacity(reference.len());
+    let mut map = CoordinateMap { segments: Vec::new() };
+    let mut position = 0;
+
+    for i in order {
+        let variant = &variants[i];
+        if variant.end() > reference.len() {
+            return Err(VariantError::OutOfBounds { variant: i });
+        }
+        if variant.position < position {
+            return Err(VariantError::Overlapping { variant: i });
+        }
+        let matches = reference[variant.position..variant.end()]
+            .iter()
+            .zip(variant.reference.iter())
+            .all(|(x, y)| x.eq_ignore_ascii_case(y));
+        if !matches {
+            return Err(VariantError::ReferenceMismatch { variant: i });
+        }
+
+        sequence.extend_from_slice(&reference[position..variant.position]);
+        map.push(variant.position - position, variant.position - position);
+
+        let (r, a) = (variant.reference.len(), variant.alternate.len());
+        sequence.extend_from_slice(&variant.alternate);
+        map.push(r.min(a), r.min(a));
+        map.push(r - r.min(a), a - r.min(a));
+
+        position = variant.end();
+    }
+
+    sequence.extend_from_slice(&reference[position..]);
+    map.push(reference.len() - position, reference.len() - position);
+
+    Ok(Mutated { sequence, map })
+}
+
+/// Грешките при четене на VCF. Всички освен `IoError` носят номера на реда (от 1).
+///
+#[derive(Debug)]
+pub enum VcfError {
+    IoError(std::io::Error),
+    MissingColumns { line_number: usize },
+    InvalidPosition { line_number: usize },
+    InvalidAllele { line_number: usize },
+    MultipleAlleles { line_number: usize },
+}
+
+impl Display for VcfError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            VcfError::IoError(e) => write!(f, "I/O error: {}", e),
+            VcfError::MissingColumns { line_number } => {
+                write!(f, "line {}: expected at least 5 tab-separated columns", line_number)
+            }
+            VcfError::InvalidPosition { line_number } => {
+                write!(f, "line {}: position must be a positive integer", line_number)
+            }
+            VcfError::InvalidAllele { line_number } => {
+                write!(f, "line {}: alleles may only contain A, C, G and T", line_number)
+            }
+            VcfError::MultipleAlleles { line_number } => {
+                write!(f, "line {}: multiple alternate alleles are not supported", line_number)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VcfError {}
+
+impl From<std::io::Error> for VcfError {
+    fn from(e: std::io::Error) -> Self {
+        VcfError::IoError(e)
+    }
+}
+
+/// Един ред от VCF -- хромозомата, id-то (ако не е `.`) и самият вариант, с позиция от 0.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcfRecord {
+    pub chrom: String,
+    pub id: Option<String>,
+    pub variant: Variant,
+}
+
+fn parse_allele(allele: &str, line_number: usize) -> Result<Vec<char>, VcfError> {
+    allele
+        .chars()
+        .map(|c| c.to_ascii_uppercase())
+        .map(|c| Nucleotide::from_char(c).map(Nucleotide::to_char).ok_or(VcfError::InvalidAllele { line_number }))
+        .collect()
+}
+
+/// Записът от реда или `None`, ако на позицията няма алтернативен алел (`ALT` е `.`).
+///
+fn parse_line(line: &str, line_number: usize) -> Result<Option<VcfRecord>, VcfError> {
+    let columns: Vec<&str> = line.split('\t').collect();
+    if columns.len() < 5 {
+        return Err(VcfError::MissingColumns { line_number });
+    }
+
+    let position = match columns[1].trim().parse::<usize>() {
+        Ok(position) if position > 0 => position - 1,
+        _ => return Err(VcfError::InvalidPosition { line_number }),
+    };
+    if columns[4].contains(',') {
+        return Err(VcfError::MultipleAlleles { line_number });
+    }
+    let reference = parse_allele(columns[3].trim(), line_number)?;
+    let alternate = match columns[4].trim() {
+        "." => return Ok(None),
+        alt => parse_allele(alt, line_number)?,
+    };
+    let id = match columns[2].trim() {
+        "." => None,
+        id => Some(id.to_string()),
+    };
+
+    let variant = Variant { position, reference, alternate };
+    Ok(Some(VcfRecord { chrom: columns[0].trim().to_string(), id, variant }))
+}
+
+/// Чете записи от минимален VCF: колоните `CHROM`, `POS` (от 1), `ID`, `REF` и `ALT`, разделени с
+/// табулации. Останалите колони се игнорират, както и празните редове и тези, които започват с
+/// `#`. Записите с `ALT` `.` (без алтернативен алел, т.е. позицията е като в референцията) също се
+/// пропускат. След първата грешка итераторът спира.
+///
+pub struct VcfReader<B> {
+    lines: Lines<B>,
+    line_number: usize,
+    done: bool,
+}
+
+impl<B: BufRead> VcfReader<B> {
+    pub fn new(reader: B) -> Self {
+        VcfReader { lines: reader.lines(), line_number: 0, done: false }
+    }
+
+    fn read_record(&mut self) -> Result<Option<VcfRecord>, VcfError> {
+        for line in &mut self.lines {
+            self.line_number += 1;
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(record) = parse_line(&line, self.line_number)? {
+                return Ok(Some(record));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<B: BufRead> Iterator for VcfReader<B> {
+    type Item = Result<VcfRecord, VcfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_record();
+        if !matches!(result, Ok(Some(_))) {
+            self.done = true;
+        }
+        result.transpose()
+    }
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+
+    const TEST_VCF: &str = "##fileformat=VCFv4.2
+#CHROM\tPOS\tID\tREF\tALT\tQUAL
+chr1\t2\trs1\tC\tT\t50
+
+chr1\t4\t.\tTA\tT\t.
+chr1\t7\t.\tg\tGCC\t.
+";
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_kinds() {
+        assert_eq!(Variant::new(0, "A", "G").kind(), VariantKind::Snp);
+        assert_eq!(Variant::new(0, "A", "ACT").kind(), VariantKind::Insertion);
+        assert_eq!(Variant::new(0, "", "CT").kind(), VariantKind::Insertion);
+        assert_eq!(Variant::new(0, "ACT", "A").kind(), VariantKind::Deletion);
+        assert_eq!(Variant::new(0, "AC", "GT").kind(), VariantKind::Complex);
+    }
+
+    #[test]
+    fn test_apply_variants() {
+        let reference = chars("ACGTACGTAC");
+        let variants = vec![Variant::new(6, "G", "GCC"), Variant::new(1, "C", "T"), Variant::new(3, "TA", "T")];
+        let mutated = apply_variants(&reference, &variants).unwrap();
+        assert_eq!(mutated.sequence, chars("ATGTCGCCTAC"));
+
+        let map = &mutated.map;
+        assert_eq!(map.to_mutated(1), Some(1));
+        assert_eq!(map.to_mutated(3), Some(3));
+        assert_eq!(map.to_mutated(4), None);
+        assert_eq!(map.to_mutated(5), Some(4));
+        assert_eq!(map.to_mutated(9), Some(10));
+        assert_eq!(map.to_mutated(10), None);
+        assert_eq!(map.to_reference(6), None);
+        assert_eq!(map.to_reference(8), Some(7));
+        for position in 0..reference.len() {
+            if let Some(mapped) = map.to_mutated(position) {
+                assert_eq!(map.to_reference(mapped), Some(position));
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_errors() {
+        let reference = chars("ACGT");
+        let apply = |variants: &[Variant]| apply_variants(&reference, variants).err();
+        assert_eq!(apply(&[Variant::new(3, "TA", "T")]), Some(VariantError::OutOfBounds { variant: 0 }));
+        assert_eq!(
+            apply(&[Variant::new(0, "A", "C"), Variant::new(1, "G", "C")]),
+            Some(VariantError::ReferenceMismatch { variant: 1 })
+        );
+        assert_eq!(
+            apply(&[Variant::new(1, "CG", "C"), Variant::new(2, "G", "A")]),
+            Some(VariantError::Overlapping { variant: 1 })
+        );
+
+        let mutated = apply_variants(&reference, &[Variant::new(2, "", "TT"), Variant::new(2, "G", "A")]).unwrap();
+        assert_eq!(mutated.sequence, chars("ACTTAT"));
+    }
+
+    #[test]
+    fn test_vcf() {
+        let records = VcfReader::new(TEST_VCF.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].chrom, "chr1");
+        assert_eq!(records[0].id, Some(String::from("rs1")));
+        assert_eq!(records[2].variant, Variant::new(6, "G", "GCC"));
+
+        let variants: Vec<Variant> = records.into_iter().map(|r| r.variant).collect();
+        assert_eq!(apply_variants(&chars("ACGTACGTAC"), &variants).unwrap().sequence, chars("ATGTCGCCTAC"));
+    }
+
+    #[test]
+    fn test_vcf_no_alternate() {
+        let input = "chr1\t3\t.\tACG\t.\nchr1\t6\trs2\tG\tC\n";
+        let records = VcfReader::new(input.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].variant, Variant::new(5, "G", "C"));
+
+        let variants: Vec<Variant> = records.into_iter().map(|r| r.variant).collect();
+        assert_eq!(apply_variants(&chars("AAACGGTT"), &variants).unwrap().sequence, chars("AAACGCTT"));
+
+        // REF пак трябва да е валиден
+        let mut reader = VcfReader::new("chr1\t3\t.\tAXG\t.\n".as_bytes());
+        assert_eq!(reader.next().unwrap().unwrap_err().to_string(), "line 1: alleles may only contain A, C, G and T");
+    }
+
+    #[test]
+    fn test_vcf_errors() {
+        let error = |input: &str| VcfReader::new(input.as_bytes()).find_map(Result::err).unwrap().to_string();
+        assert_eq!(error("#header\nchr1\t1\t.\tA\n"), "line 2: expected at least 5 tab-separated columns");
+        assert_eq!(error("chr1\t0\t.\tA\tC\n"), "line 1: position must be a positive integer");
+        assert_eq!(error("chr1\t1\t.\tA\tC\n\nchr1\t2\t.\tA\tX\n"), "line 3: alleles may only contain A, C, G and T");
+        assert_eq!(error("chr1\t1\t.\tA\tC,G\n"), "line 1: multiple alternate alleles are not supported");
+    }
+}
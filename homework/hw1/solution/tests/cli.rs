@@ -0,0 +1,96 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_solution"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Командата може да е приключила, без да чете stdin
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+const TEST_FASTA: &str = ">seq1 first\nACGT\nAA\n>seq2\nGGC\n";
+
+#[test]
+fn test_cli_count() {
+    let output = run(&["count"], TEST_FASTA);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "id\tA\tC\tG\tT\nseq1\t3\t1\t1\t1\nseq2\t0\t1\t2\t0\n");
+
+    let output = run(&["count", "--format", "json", "-"], "TGAC\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "[{\"id\":\"-\",\"a\":1,\"c\":1,\"g\":1,\"t\":1}]\n");
+}
+
+#[test]
+fn test_cli_sequences() {
+    assert_eq!(stdout(&run(&["complement"], TEST_FASTA)), ">seq1 first\nTGCATT\n>seq2\nCCG\n");
+    assert_eq!(stdout(&run(&["revcomp"], "TGAC\n")), "GTCA\n");
+    assert_eq!(stdout(&run(&["rna"], "TG\nAC\n")), "GUCA\n");
+}
+
+#[test]
+fn test_cli_errors() {
+    let output = run(&["count"], ">seq1\nACXT\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: -: seq1: line 2: Invalid nucleotide 'X' at index 2\n"
+    );
+
+    let output = run(&["count"], ">seq1\nAC\nGG\n>\nAA\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("line 4"));
+
+    assert_eq!(run(&["translate"], "").status.code(), Some(2));
+    assert_eq!(run(&["rna", "--format", "json"], "").status.code(), Some(2));
+    assert_eq!(run(&["count", "/nonexistent/file.fa"], "").status.code(), Some(1));
+}
+
+#[test]
+fn test_cli_iupac() {
+    let output = run(&["count"], ">seq1\nacgN\n");
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run(&["count", "--iupac"], ">seq1\nacgN\nAT\n");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "id\tA\tC\tG\tT\tN\tR\tY\tS\tW\tK\tM\tB\tD\tH\tV\tmasked\nseq1\t2\t1\t1\t1\t1\t0\t0\t0\t0\t0\t0\t0\t0\t0\t0\t3\n"
+    );
+
+    let output = run(&["count", "--format", "json", "--iupac"], "aR\n");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "[{\"id\":\"-\",\"a\":1,\"c\":0,\"g\":0,\"t\":0,\"n\":0,\"r\":1,\"y\":0,\"s\":0,\"w\":0,\"k\":0,\"m\":0,\
+         \"b\":0,\"d\":0,\"h\":0,\"v\":0,\"masked\":1}]\n"
+    );
+
+    assert_eq!(stdout(&run(&["complement", "--iupac"], "acgN\n")), "tgcN\n");
+    assert_eq!(stdout(&run(&["revcomp", "--iupac"], "acgN\n")), "Ncgt\n");
+    assert_eq!(stdout(&run(&["rna", "--iupac"], "aaRt\n")), "aYuu\n");
+
+    let output = run(&["rna", "--iupac"], ">seq1\nAC\nGX\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("line 3"));
+}
+
+#[test]
+fn test_cli_help() {
+    for args in [&["--help"][..], &["count", "--help"], &["rna", "-", "-h"]].iter() {
+        let output = run(args, "");
+        assert!(output.status.success());
+        assert!(stdout(&output).starts_with("Usage: solution"));
+    }
+}
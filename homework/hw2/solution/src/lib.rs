@@ -1,13 +1,35 @@
 #[derive(Debug)]
-pub struct Matrix<T: Clone> {
+pub struct Matrix<T: Clone, const R: usize = 2, const C: usize = 2> {
     // Каквито данни ви вършат работа
-    pub m: [[Cell<T>; 2]; 2] 
+    pub m: [[Cell<T>; C]; R]
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Cell<T>(pub T);
 
-impl<T: Clone> Matrix<T> {
+/// Данни, от които може да се построи матрица с `R` реда и `C` колони -- масив от редове
+/// `[[T; C]; R]` за произволен размер, или плосък масив `[T; 4]`, подреден по редове, за 2x2.
+///
+/// Ако елементите на матрицата са масиви, `Matrix::new(&[[a, b], [c, d]])` е нееднозначно (2x2 от
+/// масиви или 4x2 от числа) и типът трябва да се укаже.
+///
+pub trait MatrixData<T, const R: usize, const C: usize> {
+    fn cell(&self, i: usize, j: usize) -> &T;
+}
+
+impl<T> MatrixData<T, 2, 2> for [T; 4] {
+    fn cell(&self, i: usize, j: usize) -> &T {
+        &self[i * 2 + j]
+    }
+}
+
+impl<T, const R: usize, const C: usize> MatrixData<T, R, C> for [[T; C]; R] {
+    fn cell(&self, i: usize, j: usize) -> &T {
+        &self[i][j]
+    }
+}
+
+impl<T: Clone, const R: usize, const C: usize> Matrix<T, R, C> {
     /// Данните се очаква да бъдат подадени със статичен масив -- вижте по-долу за примери за
     /// конструиране. Какви може да са елементите? Ще тестваме само с два типа: String и i32.
     ///
@@ -17,18 +39,20 @@ impl<T: Clone> Matrix<T> {
     /// | 1 2 |
     /// | 3 4 |
     ///
+    /// За други размери подаваме масив от редове: `&[[1, 2, 3], [4, 5, 6]]` е матрица 2x3.
+    ///
     /// Забележете, че подаваме като вход някакъв slice -- reference тип. Не очакваме матрицата да
     /// държи reference, клонирайте си данните, за да имате ownership.
     ///
-    pub fn new(data: &[T; 4]) -> Matrix<T> {
-        Matrix::<T> {
-            m: [[Cell(data[0].clone()), Cell(data[1].clone())], 
-                [Cell(data[2].clone()), Cell(data[3].clone())]]
+    pub fn new<D: MatrixData<T, R, C> + ?Sized>(data: &D) -> Matrix<T, R, C> {
+        Matrix::<T, R, C> {
+            m: std::array::from_fn(|i| std::array::from_fn(|j| Cell(data.cell(i, j).clone())))
         }
     }
 
-    /// Връща вектор, който съдържа в себе си всички 4 елемента на матрицата, наредени по редове,
-    /// от ляво надясно и от горе надолу, обвити в `Cell`. Тоест, ако матрицата изглежда така:
+    /// Връща вектор, който съдържа в себе си всички R * C елемента на матрицата, наредени по
+    /// редове, от ляво надясно и от горе надолу, обвити в `Cell`. Тоест, ако матрицата изглежда
+    /// така:
     ///
     /// | 1 2 |
     /// | 3 4 |
@@ -36,18 +60,19 @@ impl<T: Clone> Matrix<T> {
     /// Очакваме `.by_row` да върне елементите в ред: 1, 2, 3, 4
     ///
     pub fn by_row(&self) -> Vec<Cell<T>> {
-        let mut vec = Vec::with_capacity(4);
+        let mut vec = Vec::with_capacity(R * C);
 
-        for i in 0..2 {
-            for j in 0..2 {
+        for i in 0..R {
+            for j in 0..C {
                 vec.push(self.m[i][j].clone());
             }
         }
         vec
     }
 
-    /// Връща вектор, който съдържа в себе си всички 4 елемента на матрицата, наредени по колони,
-    /// от горе надолу и от ляво надясно, Обвити в `Cell`. Тоест, ако матрицата изглежда така:
+    /// Връща вектор, който съдържа в себе си всички R * C елемента на матрицата, наредени по
+    /// колони, от горе надолу и от ляво надясно, Обвити в `Cell`. Тоест, ако матрицата изглежда
+    /// така:
     ///
     /// | 1 2 |
     /// | 3 4 |
@@ -55,10 +80,10 @@ impl<T: Clone> Matrix<T> {
     /// Очакваме `.by_col` да върне елементите в ред: 1, 3, 2, 4
     ///
     pub fn by_col(&self) -> Vec<Cell<T>> {
-        let mut vec = Vec::with_capacity(4);
+        let mut vec = Vec::with_capacity(R * C);
 
-        for j in 0..2 {
-            for i in 0..2 {
+        for j in 0..C {
+            for i in 0..R {
                 vec.push(self.m[i][j].clone());
             }
         }
@@ -67,63 +92,486 @@ impl<T: Clone> Matrix<T> {
 }
 
 
-use std::ops::{Add, Mul};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// Алгебрата на клетките:
+//
+// - събиране на число и низ ги слепва с интервал, в реда, в който са записани: `4 + "badger"` е
+//   `"4 badger"`, а `"badger" + 4` е `"badger 4"`. Отрицателно число обръща низа и разменя двете
+//   страни: `-4 + "xirtam"` е `"matrix 4"`, а `"xirtam" + -4` е `"4 matrix"`;
+// - два низа също се слепват с интервал, а две числа се събират както обикновено;
+// - умножение на число и низ (в който и да е ред) повтаря низа, като отрицателно число го обръща;
+// - `-` на низ го обръща, а `a - b` е `a + (-b)` -- но без да се отрицава самото число, така че
+//   `"ab" - i32::MIN` не препълва;
+// - `+`, `-`, `*` и унарният `-` на две числа не panic-ват при препълване, а се превъртат
+//   (`wrapping_*`) и в debug, и в release -- `Cell(i32::MAX) + Cell(1)` е `Cell(i32::MIN)`;
+// - всички операции работят и с `&Cell`, а `*Assign` версиите -- когато резултатът е от типа на
+//   лявата страна.
+
+fn reversed(s: &str) -> String {
+    s.chars().rev().collect()
+}
+
+impl Neg for &Cell<i32> {
+    type Output = Cell<i32>;
+
+    fn neg(self) -> Self::Output {
+        Cell(self.0.wrapping_neg())
+    }
+}
+
+impl Neg for &Cell<String> {
+    type Output = Cell<String>;
+
+    fn neg(self) -> Self::Output {
+        Cell(reversed(&self.0))
+    }
+}
+
+impl<T> Neg for Cell<T> where for<'a> &'a Cell<T>: Neg<Output = Cell<T>> {
+    type Output = Cell<T>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
 
-impl Add<Cell<String>> for Cell<i32> {
+impl Add<&Cell<String>> for &Cell<i32> {
     type Output = Cell<String>;
 
-    fn add(self, other: Cell<String>) -> Self::Output {
+    fn add(self, other: &Cell<String>) -> Self::Output {
         if self.0 >= 0 {
             Cell(format!("{} {}", self.0, other.0))
         }
         else {
-            Cell(format!("{} {}", other.0.chars().rev().collect::<String>(), -self.0))
+            Cell(format!("{} {}", reversed(&other.0), self.0.unsigned_abs()))
         }
-    } 
+    }
 }
 
-impl Mul<Cell<String>> for Cell<i32> {
+impl Add<&Cell<i32>> for &Cell<String> {
     type Output = Cell<String>;
 
-    fn mul(self, other: Cell<String>) -> Self::Output {
+    fn add(self, other: &Cell<i32>) -> Self::Output {
+        if other.0 >= 0 {
+            Cell(format!("{} {}", self.0, other.0))
+        }
+        else {
+            Cell(format!("{} {}", other.0.unsigned_abs(), reversed(&self.0)))
+        }
+    }
+}
+
+impl Add<&Cell<i32>> for &Cell<i32> {
+    type Output = Cell<i32>;
+
+    fn add(self, other: &Cell<i32>) -> Self::Output {
+        Cell(self.0.wrapping_add(other.0))
+    }
+}
+
+impl Add<&Cell<String>> for &Cell<String> {
+    type Output = Cell<String>;
+
+    fn add(self, other: &Cell<String>) -> Self::Output {
+        Cell(format!("{} {}", self.0, other.0))
+    }
+}
+
+impl Mul<&Cell<String>> for &Cell<i32> {
+    type Output = Cell<String>;
+
+    fn mul(self, other: &Cell<String>) -> Self::Output {
         if self.0 >= 0 {
             Cell(other.0.repeat(self.0 as usize))
         }
         else {
-            Cell(other.0.chars().rev().collect::<String>().repeat(-self.0 as usize))
+            Cell(reversed(&other.0).repeat(self.0.unsigned_abs() as usize))
+        }
+    }
+}
+
+impl Mul<&Cell<i32>> for &Cell<String> {
+    type Output = Cell<String>;
+
+    fn mul(self, other: &Cell<i32>) -> Self::Output {
+        other * self
+    }
+}
+
+impl Mul<&Cell<i32>> for &Cell<i32> {
+    type Output = Cell<i32>;
+
+    fn mul(self, other: &Cell<i32>) -> Self::Output {
+        Cell(self.0.wrapping_mul(other.0))
+    }
+}
+
+impl Sub<&Cell<String>> for &Cell<i32> {
+    type Output = Cell<String>;
+
+    fn sub(self, other: &Cell<String>) -> Self::Output {
+        self + &(-other)
+    }
+}
+
+impl Sub<&Cell<i32>> for &Cell<String> {
+    type Output = Cell<String>;
+
+    fn sub(self, other: &Cell<i32>) -> Self::Output {
+        if other.0 <= 0 {
+            Cell(format!("{} {}", self.0, other.0.unsigned_abs()))
+        }
+        else {
+            Cell(format!("{} {}", other.0, reversed(&self.0)))
         }
     }
 }
 
-impl Add<Matrix<String>> for Matrix<i32> {
-    type Output = Matrix<String>;
+impl Sub<&Cell<i32>> for &Cell<i32> {
+    type Output = Cell<i32>;
 
-    fn add(self, other: Matrix<String>) -> Self::Output {
-        let mut result = Matrix::new(&[String::from(""), String::from(""), String::from(""), String::from("")]);
+    fn sub(self, other: &Cell<i32>) -> Self::Output {
+        Cell(self.0.wrapping_sub(other.0))
+    }
+}
 
-        for i in 0..2 {
-            for j in 0..2 {
-                result.m[i][j] = self.m[i][j].clone() + other.m[i][j].clone();
+impl Sub<&Cell<String>> for &Cell<String> {
+    type Output = Cell<String>;
+
+    fn sub(self, other: &Cell<String>) -> Self::Output {
+        self + &(-other)
+    }
+}
+
+/// Версиите с owned операнди, чрез тези с reference.
+///
+macro_rules! forward_binop {
+    ($imp:ident, $method:ident, $lhs:ty, $rhs:ty, $out:ty) => {
+        impl $imp<$rhs> for $lhs {
+            type Output = $out;
+
+            fn $method(self, other: $rhs) -> Self::Output {
+                (&self).$method(&other)
             }
         }
-        result
+
+        impl $imp<&$rhs> for $lhs {
+            type Output = $out;
+
+            fn $method(self, other: &$rhs) -> Self::Output {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<$rhs> for &$lhs {
+            type Output = $out;
+
+            fn $method(self, other: $rhs) -> Self::Output {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add, Cell<i32>, Cell<String>, Cell<String>);
+forward_binop!(Add, add, Cell<String>, Cell<i32>, Cell<String>);
+forward_binop!(Add, add, Cell<i32>, Cell<i32>, Cell<i32>);
+forward_binop!(Add, add, Cell<String>, Cell<String>, Cell<String>);
+forward_binop!(Sub, sub, Cell<i32>, Cell<String>, Cell<String>);
+forward_binop!(Sub, sub, Cell<String>, Cell<i32>, Cell<String>);
+forward_binop!(Sub, sub, Cell<i32>, Cell<i32>, Cell<i32>);
+forward_binop!(Sub, sub, Cell<String>, Cell<String>, Cell<String>);
+forward_binop!(Mul, mul, Cell<i32>, Cell<String>, Cell<String>);
+forward_binop!(Mul, mul, Cell<String>, Cell<i32>, Cell<String>);
+forward_binop!(Mul, mul, Cell<i32>, Cell<i32>, Cell<i32>);
+
+macro_rules! forward_assign {
+    ($imp:ident, $method:ident, $op:ident, $lhs:ty, $rhs:ty) => {
+        impl $imp<&$rhs> for $lhs {
+            fn $method(&mut self, other: &$rhs) {
+                *self = (&*self).$op(other);
+            }
+        }
+
+        impl $imp<$rhs> for $lhs {
+            fn $method(&mut self, other: $rhs) {
+                *self = (&*self).$op(&other);
+            }
+        }
+    };
+}
+
+forward_assign!(AddAssign, add_assign, add, Cell<String>, Cell<i32>);
+forward_assign!(AddAssign, add_assign, add, Cell<i32>, Cell<i32>);
+forward_assign!(AddAssign, add_assign, add, Cell<String>, Cell<String>);
+forward_assign!(SubAssign, sub_assign, sub, Cell<String>, Cell<i32>);
+forward_assign!(SubAssign, sub_assign, sub, Cell<i32>, Cell<i32>);
+forward_assign!(SubAssign, sub_assign, sub, Cell<String>, Cell<String>);
+forward_assign!(MulAssign, mul_assign, mul, Cell<String>, Cell<i32>);
+forward_assign!(MulAssign, mul_assign, mul, Cell<i32>, Cell<i32>);
+
+impl<const R: usize, const C: usize> Add<Matrix<String, R, C>> for Matrix<i32, R, C> {
+    type Output = Matrix<String, R, C>;
+
+    fn add(self, other: Matrix<String, R, C>) -> Self::Output {
+        Matrix {
+            m: std::array::from_fn(|i| std::array::from_fn(|j| &self.m[i][j] + &other.m[i][j]))
+        }
     }
 }
 
-impl Mul<Matrix<String>> for Matrix<i32> {
-    type Output = String;
+impl<const R: usize, const C: usize> Matrix<i32, R, C> {
+    /// Произведението с матрица от низове, ред по колона -- клетката `(i, k)` на резултата са
+    /// `self[i][j] * other[j][k]` за `j` от 0 до C, слепени с `separator`. Членовете се слепват
+    /// дори да са празни (при умножение по 0).
+    ///
+    /// | 1 2 |   | a b |   | a cc   b dd |
+    /// | 0 1 | x | c d | = |  c       d  |    (с `separator` интервал)
+    ///
+    pub fn product<const K: usize>(&self, other: &Matrix<String, C, K>, separator: &str) -> Matrix<String, R, K> {
+        self.product_with(other, |terms| {
+            Cell(terms.into_iter().map(|cell| cell.0).collect::<Vec<_>>().join(separator))
+        })
+    }
 
-    fn mul(self, other: Matrix<String>) -> Self::Output {
-        let mut result = String::new();
+    /// Като `product`, но членовете на всяка клетка (в реда на `j`) се обединяват с `join`.
+    ///
+    pub fn product_with<const K: usize, F>(&self, other: &Matrix<String, C, K>, join: F) -> Matrix<String, R, K>
+    where
+        F: Fn(Vec<Cell<String>>) -> Cell<String>,
+    {
+        Matrix {
+            m: std::array::from_fn(|i| {
+                std::array::from_fn(|k| join((0..C).map(|j| &self.m[i][j] * &other.m[j][k]).collect()))
+            })
+        }
+    }
+}
+
+/// Произведението с матрица от низове -- R x C по C x K дава R x K, като `product` с разделител
+/// интервал.
+///
+impl<const R: usize, const C: usize, const K: usize> Mul<Matrix<String, C, K>> for Matrix<i32, R, C> {
+    type Output = Matrix<String, R, K>;
+
+    fn mul(self, other: Matrix<String, C, K>) -> Self::Output {
+        self.product(&other, " ")
+    }
+}
+
+impl<const R: usize, const C: usize> Matrix<String, R, C> {
+    /// Клетките по главния диагонал, слепени с интервал. За произведение на квадратни матрици това
+    /// е резултатът от умножението в първоначалното условие -- `self[i][j] * other[j][i]` за всяко
+    /// `i` и `j`, по редове:
+    ///
+    /// | 1 2 |   | one   two  |
+    /// | 1 1 | x | three four | -> "one threethree two four"
+    ///
+    pub fn trace(&self) -> String {
+        (0..R.min(C)).map(|i| self.m[i][i].0.as_str()).collect::<Vec<_>>().join(" ")
+    }
+}
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Как се записва и чете една клетка в нотацията `| a b |`. Низ, който е една дума, се записва
+/// както си е. Останалите -- празни, с интервали, `"`, `\\`, `|` или контролни символи -- са в
+/// кавички, като вътре `"`, `\\`, нов ред, табулация и `\r` се записват като `\"`, `\\`, `\n`, `\t`
+/// и `\r`.
+///
+pub trait Notation: Clone {
+    fn to_token(&self) -> String;
+    fn from_token(token: &str) -> Option<Self>;
+}
+
+impl Notation for i32 {
+    fn to_token(&self) -> String {
+        self.to_string()
+    }
 
-        for i in 0..2 {
-            for j in 0..2 {
-                result = format!("{} {}", result, (self.m[i][j].clone() * other.m[j][i].clone()).0);
+    fn from_token(token: &str) -> Option<Self> {
+        token.parse().ok()
+    }
+}
+
+impl Notation for String {
+    fn to_token(&self) -> String {
+        let plain = !self.is_empty()
+            && !self.chars().any(|c| c.is_whitespace() || c.is_control() || matches!(c, '"' | '\\' | '|'));
+        if plain {
+            return self.clone();
+        }
+
+        let mut result = String::from("\"");
+        for c in self.chars() {
+            match c {
+                '"' => result.push_str("\\\""),
+                '\\' => result.push_str("\\\\"),
+                '\n' => result.push_str("\\n"),
+                '\t' => result.push_str("\\t"),
+                '\r' => result.push_str("\\r"),
+                c => result.push(c),
             }
         }
-        result.remove(0);
+        result.push('"');
         result
     }
+
+    fn from_token(token: &str) -> Option<Self> {
+        let quoted = match token.strip_prefix('"') {
+            Some(rest) => rest.strip_suffix('"')?,
+            None if token.contains(['"', '|']) => return None,
+            None => return Some(token.to_string()),
+        };
+
+        let mut result = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => result.push(match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    c => c,
+                }),
+                '"' => return None,
+                c => result.push(c),
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Матрицата като в условието -- по един ред `| a b |` на ред от матрицата, с подравнени колони:
+///
+/// | "1 one"   "2 two"  |
+/// | "3 three" "4 four" |
+///
+impl<T: Notation, const R: usize, const C: usize> Display for Matrix<T, R, C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let tokens: Vec<Vec<String>> =
+            self.m.iter().map(|row| row.iter().map(|cell| cell.0.to_token()).collect()).collect();
+        let widths: Vec<usize> =
+            (0..C).map(|j| tokens.iter().map(|row| row[j].chars().count()).max().unwrap_or(0)).collect();
+
+        for (i, row) in tokens.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "|")?;
+            for (token, &width) in row.iter().zip(widths.iter()) {
+                write!(f, " {}{}", token, " ".repeat(width - token.chars().count()))?;
+            }
+            write!(f, " |")?;
+        }
+        Ok(())
+    }
+}
+
+/// Грешките при четене на матрица. Редовете и колоните са номерирани от 1, като празните редове
+/// във входа не се броят.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMatrixError {
+    RowCount { expected: usize, found: usize },
+    MissingBars { row: usize },
+    UnterminatedQuote { row: usize },
+    RowLength { row: usize, expected: usize, found: usize },
+    InvalidElement { row: usize, column: usize, token: String },
+}
+
+impl Display for ParseMatrixError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ParseMatrixError::RowCount { expected, found } => {
+                write!(f, "expected {} rows, found {}", expected, found)
+            }
+            ParseMatrixError::MissingBars { row } => {
+                write!(f, "row {}: expected `| ... |`", row)
+            }
+            ParseMatrixError::UnterminatedQuote { row } => {
+                write!(f, "row {}: unterminated string", row)
+            }
+            ParseMatrixError::RowLength { row, expected, found } => {
+                write!(f, "row {}: expected {} elements, found {}", row, expected, found)
+            }
+            ParseMatrixError::InvalidElement { row, column, token } => {
+                write!(f, "row {}, column {}: invalid element {}", row, column, token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseMatrixError {}
+
+/// Разделя реда на думи по интервалите, без тези в кавички. Връща `None` при незатворени кавички.
+///
+fn split_tokens(line: &str) -> Option<Vec<&str>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if let Some(start) = start.take() {
+                    tokens.push(&line[start..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+
+    if quoted {
+        return None;
+    }
+    if let Some(start) = start {
+        tokens.push(&line[start..]);
+    }
+    Some(tokens)
+}
+
+impl<T: Notation, const R: usize, const C: usize> FromStr for Matrix<T, R, C> {
+    type Err = ParseMatrixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        if lines.len() != R {
+            return Err(ParseMatrixError::RowCount { expected: R, found: lines.len() });
+        }
+
+        let mut cells: Vec<Vec<T>> = Vec::with_capacity(R);
+        for (i, line) in lines.iter().enumerate() {
+            let row = i + 1;
+            let inner = match line.strip_prefix('|').and_then(|line| line.strip_suffix('|')) {
+                Some(inner) => inner,
+                None => return Err(ParseMatrixError::MissingBars { row }),
+            };
+            let tokens = split_tokens(inner).ok_or(ParseMatrixError::UnterminatedQuote { row })?;
+            if tokens.len() != C {
+                return Err(ParseMatrixError::RowLength { row, expected: C, found: tokens.len() });
+            }
+
+            let parsed = tokens.iter().enumerate().map(|(j, token)| {
+                T::from_token(token).ok_or_else(|| ParseMatrixError::InvalidElement {
+                    row,
+                    column: j + 1,
+                    token: token.to_string(),
+                })
+            });
+            cells.push(parsed.collect::<Result<_, _>>()?);
+        }
+
+        Ok(Matrix { m: std::array::from_fn(|i| std::array::from_fn(|j| Cell(cells[i][j].clone()))) })
+    }
 }
 
 #[cfg(test)]
@@ -161,11 +609,166 @@ mod custom_tests {
         let mat1 = Matrix::new(&[String::from("this"), String::from("is"), 
                                 String::from("a"), String::from("matrix")]);
         let mat = Matrix::<i32>::new(&[1,2,3,4]);
-        assert_eq!(mat * mat1, String::from("this aa isisis matrixmatrixmatrixmatrix"));
+        assert_eq!(
+            (mat * mat1).by_row(),
+            string_cell_vec("this aa", "is matrixmatrix", "thisthisthis aaaa", "isisis matrixmatrixmatrixmatrix")
+        );
 
         let mat1 = Matrix::new(&[String::from("this"), String::from("is"), 
                                 String::from("a"), String::from("matrix")]);
         let mat2 = Matrix::new(&[-1,0,2,1]);
-        assert_eq!(mat2 * mat1, String::from("siht  isis matrix"));
+        let product = mat2 * mat1;
+        assert_eq!(product.by_row(), string_cell_vec("siht ", "si ", "thisthis a", "isis matrix"));
+        assert_eq!(product.trace(), "siht  isis matrix");
+    }
+
+    #[test]
+    fn test_any_size() {
+        let mat = Matrix::new(&[[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(mat.by_row(), vec![Cell(1), Cell(2), Cell(3), Cell(4), Cell(5), Cell(6)]);
+        assert_eq!(mat.by_col(), vec![Cell(1), Cell(4), Cell(2), Cell(5), Cell(3), Cell(6)]);
+
+        let words = Matrix::new(&[["a", "b", "c"], ["d", "e", "f"]].map(|row| row.map(String::from)));
+        let sum = Matrix::new(&[[1, -2, 0], [3, 0, -1]]) + words;
+        assert_eq!(sum.by_row()[1], Cell(String::from("b 2")));
+        assert_eq!(sum.by_row()[5], Cell(String::from("f 1")));
+
+        let column = Matrix::new(&[[String::from("x")], [String::from("y")], [String::from("z")]]);
+        assert_eq!((Matrix::new(&[[1, 2, 3]]) * column).by_row(), vec![Cell(String::from("x yy zzz"))]);
+
+        let words = Matrix::new(&[["a", "b"], ["c", "d"], ["e", "f"]].map(|row| row.map(String::from)));
+        let product: Matrix<String, 1, 2> = Matrix::new(&[[1, 0, 2]]) * words;
+        assert_eq!(product.by_row(), vec![Cell(String::from("a  ee")), Cell(String::from("b  ff"))]);
+    }
+
+    #[test]
+    fn test_string_product() {
+        let words = Matrix::new(&[String::from("a"), String::from("b"), String::from("c"), String::from("d")]);
+        let product = Matrix::new(&[1, 2, 0, -1]).product(&words, " ");
+        assert_eq!(product.by_row(), vec![
+            Cell(String::from("a cc")), Cell(String::from("b dd")),
+            Cell(String::from(" c")), Cell(String::from(" d")),
+        ]);
+
+        let column = Matrix::new(&[[String::from("ab")], [String::from("x")], [String::from("yz")]]);
+        let product = Matrix::new(&[[1, 2, 1], [-1, 0, 2]]).product(&column, "");
+        assert_eq!(product.by_row(), vec![Cell(String::from("abxxyz")), Cell(String::from("bayzyz"))]);
+
+        let words = Matrix::new(&[[String::from("a"), String::from("b")]]);
+        let product = Matrix::new(&[[2], [1]]).product_with(&words, |terms| &Cell(String::from("<")) + &terms[0]);
+        assert_eq!(product.by_col(), vec![
+            Cell(String::from("< aa")), Cell(String::from("< a")),
+            Cell(String::from("< bb")), Cell(String::from("< b")),
+        ]);
+
+        let words = Matrix::new(&[String::from("a"), String::from("b"), String::from("c"), String::from("d")]);
+        let composed = Matrix::new(&[1, 0, 0, -1]) + Matrix::new(&[1, 1, 1, 1]).product(&words, "-");
+        assert_eq!(composed.by_row()[0], Cell(String::from("1 a-c")));
+        assert_eq!(composed.by_row()[3], Cell(String::from("d-b 1")));
+    }
+
+    #[test]
+    fn test_cell_algebra() {
+        let word = Cell(String::from("xirtam"));
+        let (four, minus_four) = (Cell(4), Cell(-4));
+
+        assert_eq!(&word + &four, Cell(String::from("xirtam 4")));
+        assert_eq!(&word + &minus_four, Cell(String::from("4 matrix")));
+        assert_eq!(&minus_four + &word, Cell(String::from("matrix 4")));
+        assert_eq!(Cell(2) + Cell(3), Cell(5));
+        assert_eq!(Cell(String::from("a")) + Cell(String::from("b")), Cell(String::from("a b")));
+
+        assert_eq!(-&word, Cell(String::from("matrix")));
+        assert_eq!(-Cell(7), Cell(-7));
+        assert_eq!(&four - &word, &four + &(-&word));
+        assert_eq!(&word - &four, Cell(String::from("4 matrix")));
+        assert_eq!(Cell(2) - Cell(3), Cell(-1));
+        assert_eq!(Cell(String::from("ab")) - Cell(String::from("cd")), Cell(String::from("ab dc")));
+
+        assert_eq!(&word * &Cell(2), &Cell(2) * &word);
+        assert_eq!(Cell(String::from("ab")) * Cell(-2), Cell(String::from("baba")));
+        assert_eq!(Cell(6) * &Cell(7), Cell(42));
+
+        let mut cell = Cell(String::from("a"));
+        cell += Cell(String::from("b"));
+        cell -= &Cell(-1);
+        cell *= Cell(2);
+        assert_eq!(cell, Cell(String::from("a b 1a b 1")));
+
+        let mut number = Cell(3);
+        number += &Cell(4);
+        number -= Cell(2);
+        number *= Cell(-2);
+        assert_eq!(number, Cell(-10));
+    }
+
+    #[test]
+    fn test_cell_algebra_extremes() {
+        assert_eq!(Cell(-1) - Cell(i32::MIN), Cell(i32::MAX));
+        assert_eq!(Cell(i32::MAX) + Cell(1), Cell(i32::MIN));
+        assert_eq!(Cell(0) - Cell(i32::MIN), Cell(i32::MIN));
+        assert_eq!(Cell(i32::MAX) * Cell(2), Cell(-2));
+        assert_eq!(Cell(String::from("ab")) - Cell(i32::MIN), Cell(String::from("ab 2147483648")));
+        assert_eq!(Cell(String::from("ab")) - Cell(i32::MAX), Cell(String::from("2147483647 ba")));
+        assert_eq!(Cell(String::from("ab")) - Cell(0), Cell(String::from("ab 0")));
+        assert_eq!(Cell(i32::MIN) + Cell(String::from("ab")), Cell(String::from("ba 2147483648")));
+        assert_eq!(Cell(i32::MIN) - Cell(String::from("ab")), Cell(String::from("ab 2147483648")));
+        assert_eq!(-Cell(i32::MIN), Cell(i32::MIN));
+
+        let mut cell = Cell(String::from("ab"));
+        cell -= Cell(i32::MIN);
+        assert_eq!(cell, Cell(String::from("ab 2147483648")));
+    }
+
+    #[test]
+    fn test_display() {
+        let sum = Matrix::new(&[1, 2, 3, 4]) + Matrix::new(&[
+            String::from("one"), String::from("two"),
+            String::from("three"), String::from("four")
+        ]);
+        assert_eq!(sum.to_string(), "| \"1 one\"   \"2 two\"  |\n| \"3 three\" \"4 four\" |");
+        assert_eq!(Matrix::new(&[[10, -2, 3]]).to_string(), "| 10 -2 3 |");
+        assert_eq!(Matrix::new(&[[String::from("say \"hi\"")]]).to_string(), "| \"say \\\"hi\\\"\" |");
+
+        let words = [["one", "", "a|b"], ["two\nlines", "tab\there", "back\\slash"]];
+        let words = Matrix::new(&words.map(|row| row.map(String::from)));
+        assert_eq!(
+            words.to_string(),
+            "| one          \"\"          \"a|b\"         |\n| \"two\\nlines\" \"tab\\there\" \"back\\\\slash\" |"
+        );
+        assert_eq!(words.to_string().parse::<Matrix<String, 2, 3>>().unwrap().by_row(), words.by_row());
+    }
+
+    #[test]
+    fn test_parse() {
+        let mat: Matrix<i32> = "| 1 2 |\n|  3   4 |".parse().unwrap();
+        assert_eq!(mat.by_row(), vec![Cell(1), Cell(2), Cell(3), Cell(4)]);
+
+        let words: Matrix<String, 2, 2> = "
+            | \"you get it\" two |
+            | \"\"           \"a \\\"b\\\"\" |
+        ".parse().unwrap();
+        assert_eq!(words.by_row()[0], Cell(String::from("you get it")));
+        assert_eq!(words.by_row()[2], Cell(String::new()));
+        assert_eq!(words.by_row()[3], Cell(String::from("a \"b\"")));
+
+        let sum = Matrix::new(&[-1, 0, 2, 1]) + words;
+        assert_eq!(sum.to_string().parse::<Matrix<String>>().unwrap().by_row(), sum.by_row());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| s.parse::<Matrix<i32>>().unwrap_err();
+        assert_eq!(parse("| 1 2 |"), ParseMatrixError::RowCount { expected: 2, found: 1 });
+        assert_eq!(parse("| 1 2 |\n| 3 4 5 |"), ParseMatrixError::RowLength { row: 2, expected: 2, found: 3 });
+        assert_eq!(parse("| 1 2 |\n 3 4 |"), ParseMatrixError::MissingBars { row: 2 });
+        assert_eq!(
+            parse("| 1 x |\n| 3 4 |"),
+            ParseMatrixError::InvalidElement { row: 1, column: 2, token: String::from("x") }
+        );
+        assert_eq!(
+            "| \"a b |\n| c d |".parse::<Matrix<String>>().unwrap_err().to_string(),
+            "row 1: unterminated string"
+        );
     }
 }
@@ -135,7 +135,10 @@ fn test_multiplying_matrices_1() {
         String::from("three"), String::from("you get it")
     ]);
 
-    assert_eq!(matrix1 * matrix2, String::from("one threethree twotwotwo you get it"));
+    assert_eq!(
+        (matrix1 * matrix2).by_row(),
+        string_cell_vec!["one threethree", "two you get ityou get it", "oneoneone three", "twotwotwo you get it"]
+    );
 }
 
 #[test]
@@ -149,5 +152,8 @@ fn test_multiplying_matrices_2() {
         String::from(" "), String::from("четири ")
     ]);
 
-    assert_eq!(matrix1 * matrix2, String::from("едно  евдевдевд  иритеч иритеч"));
+    assert_eq!(
+        (matrix1 * matrix2).by_row(),
+        string_cell_vec!["едно ", "две ", "ондеондеонде   ", "евдевдевд  иритеч иритеч"]
+    );
 }
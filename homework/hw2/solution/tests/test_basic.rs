@@ -55,19 +55,22 @@ fn test_basic() {
         String::from("one"), String::from("two"),
         String::from("three"), String::from("four")
     ]);
-    assert_eq!(matrix1 * matrix2, String::from("one threethree two four"));
+    assert_eq!(
+        (matrix1 * matrix2).by_row(),
+        string_cell_vec("one threethree", "two fourfour", "one three", "two four")
+    );
     assert_eq!(
         (Matrix::new(&[1, 2, -3, 4]) * Matrix::new(&[
                                         String::from("one"), String::from("two"),
                                         String::from("abac"), String::from("four")
-                                    ])),
-        String::from("one abacabac owtowtowt fourfourfourfour")
+                                    ])).by_row(),
+        string_cell_vec("one abacabac", "two fourfour", "enoenoeno abacabacabacabac", "owtowtowt fourfourfourfour")
     );
     assert_eq!(
         (Matrix::new(&[0, 1, -3, 4]) * Matrix::new(&[
                                         String::from("one"), String::from("two"),
                                         String::from("abac"), String::from("four")
-                                    ])),
-        String::from(" abac owtowtowt fourfourfourfour")
+                                    ])).by_row(),
+        string_cell_vec(" abac", " four", "enoenoeno abacabacabacabac", "owtowtowt fourfourfourfour")
     );
 }
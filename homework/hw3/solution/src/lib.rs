@@ -1,8 +1,11 @@
-use std::collections::{HashMap, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
 use std::io::BufRead;
 use std::str::FromStr;
 
+pub mod routes;
+
 /// Различните грешки, които ще очакваме да върнете като резултат от някои невалидни операции.
 /// Повече детайли по-долу.
 ///
@@ -18,7 +21,7 @@ pub enum Errors {
 /// Четирите посоки, в които може една стая да има съседи. Може да добавите още trait
 /// имплементации, за да си улесните живота.
 ///
-#[derive(Clone, Copy, Hash, Debug)]
+#[derive(Clone, Copy, Hash, Debug, PartialEq, Eq)]
 pub enum Direction {
     North,
     South,
@@ -62,12 +65,14 @@ impl FromStr for Direction {
     }
 }
 
-impl PartialEq for Direction {
-    fn eq(&self, other: &Self) -> bool {
-        self.to_string() == other.to_string()
-    }
-}
-impl Eq for Direction {}
+/// Редът, в който обхождаме съседите на една стая, за да са търсенията детерминистични.
+///
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::East,
+    Direction::West,
+];
 
 /// Една стая в подземията. Дефинира се само с име, макар че в по-интересна имплементация може да
 /// държи item-и, противници...
@@ -79,12 +84,24 @@ pub struct Room {
     pub adj: HashMap<Direction, String>,
 }
 
+impl Room {
+    /// Имената на съседите на стаята, в реда на `DIRECTIONS`.
+    ///
+    fn neighbours(&self) -> impl Iterator<Item = &str> {
+        DIRECTIONS
+            .iter()
+            .filter_map(move |direction| self.adj.get(direction).map(String::as_str))
+    }
+}
+
 /// Контейнер за стаите и не само. Ще работим предимно със тази структура.
 ///
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Dungeon {
     // Каквито полета ви трябват
     rooms: HashMap<String, Room>,
+    // Колко агента побира всяка стая, която не побира точно един -- вижте `routes`
+    capacities: HashMap<String, usize>,
 }
 
 impl Dungeon {
@@ -93,6 +110,7 @@ impl Dungeon {
     pub fn new() -> Self {
         Dungeon {
             rooms: HashMap::new(),
+            capacities: HashMap::new(),
         }
     }
 
@@ -212,7 +230,7 @@ impl Dungeon {
                     .iter()
                     .collect::<String>()
                     .split(" -> ")
-                    .map(|s| String::from(s))
+                    .map(String::from)
                     .collect();
                 if words.len() != 3 {
                     return Err(Errors::LineParseError { line_number });
@@ -250,7 +268,7 @@ impl Dungeon {
                         }
                         continue;
                     }
-                    if line == "" {
+                    if line.is_empty() {
                         reading_state = ReadingState::EmptyLine;
                         continue;
                     }
@@ -292,46 +310,244 @@ impl Dungeon {
         start_room_name: &str,
         end_room_name: &str,
     ) -> Result<Option<Vec<&Room>>, Errors> {
-        if start_room_name == end_room_name {
-            return Ok(Some(vec![self.get_room(start_room_name)?]));
+        let start_room = self.get_room(start_room_name)?;
+        let end_room = self.get_room(end_room_name)?;
+
+        match self.bfs(&start_room.name, &end_room.name, &HashSet::new(), &HashSet::new())? {
+            Some(path) => Ok(Some(self.path_rooms(&path)?)),
+            None => Ok(None),
         }
+    }
 
+    /// Итератор по всички прости пътища (без повтарящи се стаи) от `start_room_name` до
+    /// `end_room_name`, които минават през най-много `max_depth` връзки. Пътищата се генерират
+    /// lazy, с обхождане в дълбочина.
+    ///
+    /// Ако някоя от двете стаи не съществува, очакваме `Errors::UnknownRoom`.
+    ///
+    pub fn all_paths(
+        &self,
+        start_room_name: &str,
+        end_room_name: &str,
+        max_depth: usize,
+    ) -> Result<AllPaths<'_>, Errors> {
+        let start_room = self.get_room(start_room_name)?;
+        let end_room = self.get_room(end_room_name)?;
+
+        Ok(AllPaths::new(self, start_room, &end_room.name, max_depth))
+    }
+
+    /// Итератор по простите пътища от `start_room_name` до `end_room_name`, подредени по дължина
+    /// (алгоритъм на Yen). За първите `k` най-кратки пътя -- `.take(k)`.
+    ///
+    /// Ако някоя от двете стаи не съществува, очакваме `Errors::UnknownRoom`.
+    ///
+    pub fn shortest_paths(
+        &self,
+        start_room_name: &str,
+        end_room_name: &str,
+    ) -> Result<ShortestPaths<'_>, Errors> {
+        let start_room = self.get_room(start_room_name)?;
+        let end_room = self.get_room(end_room_name)?;
+
+        Ok(ShortestPaths {
+            dungeon: self,
+            start: &start_room.name,
+            end: &end_room.name,
+            found: Vec::new(),
+            candidates: BinaryHeap::new(),
+        })
+    }
+
+    /// Търсене в ширина от `start` до `end`, което заобикаля стаите в `banned_rooms` и връзките в
+    /// `banned_links`. Връща имената на стаите по пътя.
+    ///
+    fn bfs<'a>(
+        &'a self,
+        start: &'a str,
+        end: &str,
+        banned_rooms: &HashSet<&str>,
+        banned_links: &HashSet<(&str, &str)>,
+    ) -> Result<Option<Vec<&'a str>>, Errors> {
         let mut q = VecDeque::<&str>::new();
-        let mut checked = HashMap::<&str, Option<&str>>::new();
-        q.push_back(self.get_room(start_room_name)?.name.as_str());
-        let mut parent = None;
+        let mut parents = HashMap::<&str, Option<&str>>::new();
+        q.push_back(start);
+        parents.insert(start, None);
+
+        while let Some(current) = q.pop_front() {
+            if current == end {
+                let mut path = VecDeque::new();
+                let mut room = Some(current);
+                while let Some(name) = room {
+                    path.push_front(name);
+                    room = parents[name];
+                }
+                return Ok(Some(path.into()));
+            }
 
-        while q.len() > 0 {
-            let current = q.pop_front().unwrap();
+            for next in self.get_room(current)?.neighbours() {
+                if parents.contains_key(next)
+                    || banned_rooms.contains(next)
+                    || banned_links.contains(&(current, next))
+                {
+                    continue;
+                }
+                parents.insert(next, Some(current));
+                q.push_back(next);
+            }
+        }
 
-            if checked.contains_key(current) {
+        Ok(None)
+    }
+
+    fn path_rooms(&self, path: &[&str]) -> Result<Vec<&Room>, Errors> {
+        path.iter().map(|name| self.get_room(name)).collect()
+    }
+}
+
+/// Итератор, който връща `Dungeon::all_paths`.
+///
+pub struct AllPaths<'a> {
+    dungeon: &'a Dungeon,
+    end: &'a str,
+    max_depth: usize,
+    // Текущият път и за всяка стая в него -- индекса на следващата посока за проверка
+    path: Vec<&'a Room>,
+    cursors: Vec<usize>,
+    // Пътят от стая до самата нея, който няма как да продължи
+    reflexive: Option<Vec<&'a Room>>,
+}
+
+impl<'a> AllPaths<'a> {
+    fn new(dungeon: &'a Dungeon, start: &'a Room, end: &'a str, max_depth: usize) -> Self {
+        let mut paths = AllPaths {
+            dungeon,
+            end,
+            max_depth,
+            path: Vec::new(),
+            cursors: Vec::new(),
+            reflexive: None,
+        };
+
+        if start.name == end {
+            paths.reflexive = Some(vec![start]);
+        } else {
+            paths.path.push(start);
+            paths.cursors.push(0);
+        }
+        paths
+    }
+}
+
+impl<'a> Iterator for AllPaths<'a> {
+    type Item = Vec<&'a Room>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(path) = self.reflexive.take() {
+            return Some(path);
+        }
+
+        while let Some(&current) = self.path.last() {
+            let cursor = self.cursors.last_mut().unwrap();
+
+            if *cursor >= DIRECTIONS.len() || self.path.len() > self.max_depth {
+                self.path.pop();
+                self.cursors.pop();
                 continue;
             }
 
-            checked.insert(current, parent);
+            let direction = DIRECTIONS[*cursor];
+            *cursor += 1;
 
-            if current == end_room_name {
-                break;
+            let next = match current.adj.get(&direction) {
+                Some(name) => match self.dungeon.get_room(name) {
+                    Ok(room) => room,
+                    Err(_) => continue,
+                },
+                None => continue,
+            };
+            if self.path.iter().any(|room| room.name == next.name) {
+                continue;
             }
 
-            for (_, v) in self.get_room(current)?.adj.iter() {
-                q.push_back(v);
+            if next.name == self.end {
+                let mut result = self.path.clone();
+                result.push(next);
+                return Some(result);
             }
 
-            parent = Some(current);
+            self.path.push(next);
+            self.cursors.push(0);
         }
 
-        if !checked.contains_key(end_room_name) {
-            return Ok(None);
-        }
+        None
+    }
+}
 
-        let mut path = VecDeque::new();
-        path.push_front(self.get_room(end_room_name)?);
-        while parent != None {
-            path.push_front(self.get_room(parent.unwrap())?);
-            parent = checked[parent.unwrap()];
+/// Итератор, който връща `Dungeon::shortest_paths`.
+///
+pub struct ShortestPaths<'a> {
+    dungeon: &'a Dungeon,
+    start: &'a str,
+    end: &'a str,
+    found: Vec<Vec<&'a str>>,
+    candidates: BinaryHeap<Reverse<(usize, Vec<&'a str>)>>,
+}
+
+impl<'a> ShortestPaths<'a> {
+    /// Добавя кандидатите, които се отклоняват от последния намерен път.
+    ///
+    fn add_candidates(&mut self) {
+        let last = match self.found.last() {
+            Some(path) => path.clone(),
+            None => return,
+        };
+
+        for i in 0..last.len() - 1 {
+            let spur = last[i];
+            let root = &last[..=i];
+
+            let banned_links: HashSet<(&str, &str)> = self
+                .found
+                .iter()
+                .filter(|path| path.len() > i + 1 && path[..=i] == *root)
+                .map(|path| (path[i], path[i + 1]))
+                .collect();
+            let banned_rooms: HashSet<&str> = root[..i].iter().copied().collect();
+
+            if let Ok(Some(spur_path)) = self.dungeon.bfs(spur, self.end, &banned_rooms, &banned_links) {
+                let mut path = root[..i].to_vec();
+                path.extend(spur_path);
+
+                if !self.found.contains(&path) {
+                    self.candidates.push(Reverse((path.len(), path)));
+                }
+            }
         }
-        Ok(Some(path.into()))
+    }
+}
+
+impl<'a> Iterator for ShortestPaths<'a> {
+    type Item = Vec<&'a Room>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let path = if self.found.is_empty() {
+            self.dungeon
+                .bfs(self.start, self.end, &HashSet::new(), &HashSet::new())
+                .ok()??
+        } else {
+            self.add_candidates();
+            loop {
+                let Reverse((_, path)) = self.candidates.pop()?;
+                if !self.found.contains(&path) {
+                    break path;
+                }
+            }
+        };
+
+        let rooms = self.dungeon.path_rooms(&path).ok();
+        self.found.push(path);
+        rooms
     }
 }
 
@@ -489,4 +705,57 @@ mod custom_tests {
         let dungeon = Dungeon::from_reader(TEST_INPUT_1.trim().as_bytes()).unwrap();
         assert!(matches!(dungeon.find_path("room2", "room1"), Ok(None)));
     }
+
+    const TEST_INPUT_BOSS: &str = "
+## Rooms
+- Entrance
+- Hall
+- Closet
+- Boss
+- Corridor
+- Treasure
+
+## Links
+- Entrance -> East -> Hall
+- Entrance -> South -> Closet
+- Hall -> South -> Boss
+- Closet -> East -> Boss
+- Boss -> East -> Treasure
+- Hall -> East -> Corridor
+- Corridor -> South -> Treasure
+";
+
+    fn names(path: Vec<&Room>) -> Vec<&str> {
+        path.into_iter().map(|room| room.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_dungeon_all_paths() {
+        let dungeon = Dungeon::from_reader(TEST_INPUT_BOSS.trim().as_bytes()).unwrap();
+
+        let paths = dungeon.all_paths("Entrance", "Treasure", 10).unwrap().map(names).collect::<Vec<_>>();
+        assert_eq!(paths.len(), 4);
+        assert_eq!(paths.iter().filter(|path| !path.contains(&"Boss")).count(), 1);
+        assert!(paths.contains(&vec!["Entrance", "Closet", "Boss", "Hall", "Corridor", "Treasure"]));
+
+        assert_eq!(dungeon.all_paths("Entrance", "Treasure", 3).unwrap().count(), 3);
+        assert_eq!(dungeon.all_paths("Entrance", "Treasure", 2).unwrap().count(), 0);
+        assert_eq!(names(dungeon.all_paths("Hall", "Hall", 3).unwrap().next().unwrap()), vec!["Hall"]);
+        assert!(dungeon.all_paths("Entrance", "Dragon", 3).is_err());
+    }
+
+    #[test]
+    fn test_dungeon_shortest_paths() {
+        let dungeon = Dungeon::from_reader(TEST_INPUT_BOSS.trim().as_bytes()).unwrap();
+
+        let paths = dungeon.shortest_paths("Entrance", "Treasure").unwrap().map(names).collect::<Vec<_>>();
+        assert_eq!(paths.iter().map(|path| path.len()).collect::<Vec<_>>(), vec![4, 4, 4, 6]);
+        assert_eq!(paths[3], vec!["Entrance", "Closet", "Boss", "Hall", "Corridor", "Treasure"]);
+
+        assert_eq!(dungeon.shortest_paths("Entrance", "Treasure").unwrap().take(2).count(), 2);
+
+        let dungeon = Dungeon::from_reader(TEST_INPUT_1.trim().as_bytes()).unwrap();
+        assert!(dungeon.shortest_paths("room2", "room1").unwrap().next().is_none());
+        assert!(dungeon.shortest_paths("room0", "room1").is_err());
+    }
 }
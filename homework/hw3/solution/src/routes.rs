@@ -0,0 +1,409 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::{Dungeon, Errors, Room};
+
+/// Колко възела най-много разглежда `plan_routes`, преди да се откаже. При неразрешими задачи
+/// дървото на ограниченията расте експоненциално с `max_steps`.
+///
+pub const MAX_NODES: usize = 5_000;
+
+/// Един агент -- група от герои или патрулиращ NPC. Тръгва от `start`, минава по ред през
+/// стаите, добавени с `via`, и накрая остава в `goal`.
+///
+#[derive(Debug, Clone)]
+pub struct Agent {
+    waypoints: Vec<String>,
+}
+
+impl Agent {
+    /// Агент, който трябва да стигне от `start` до `goal`.
+    ///
+    pub fn new(start: &str, goal: &str) -> Self {
+        Agent {
+            waypoints: vec![start.into(), goal.into()],
+        }
+    }
+
+    /// Добавя междинна стая, през която агентът трябва да мине преди `goal`. Патрул, който се
+    /// връща там, откъдето е тръгнал, е `Agent::new("Gate", "Gate").via("Hall")`.
+    ///
+    pub fn via(mut self, room_name: &str) -> Self {
+        let goal = self.waypoints.len() - 1;
+        self.waypoints.insert(goal, room_name.into());
+        self
+    }
+}
+
+/// Ограничение, което търсенето на високо ниво налага на един агент.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Constraint<'a> {
+    /// Агентът не може да е в стаята в дадената стъпка.
+    Room(&'a str, usize),
+    /// Агентът не може да мине от първата стая във втората, тръгвайки в дадената стъпка.
+    Move(&'a str, &'a str, usize),
+}
+
+/// Сблъсък между маршрутите на няколко агента.
+///
+enum Conflict<'a> {
+    /// Агентите са повече, отколкото стаята побира -- поне един от тях не трябва да е там.
+    Capacity(&'a str, usize, Vec<usize>),
+    /// Двама агента си разменят местата през една и съща врата.
+    Swap(usize, usize, &'a str, &'a str, usize),
+}
+
+impl<'a> Conflict<'a> {
+    /// Разклоненията, всяко от които забранява сблъсъка на един от агентите.
+    ///
+    fn branches(&self) -> Vec<(usize, Constraint<'a>)> {
+        match *self {
+            Conflict::Capacity(room, time, ref agents) => agents
+                .iter()
+                .map(|&agent| (agent, Constraint::Room(room, time)))
+                .collect(),
+            Conflict::Swap(first, second, from, to, time) => vec![
+                (first, Constraint::Move(from, to, time)),
+                (second, Constraint::Move(to, from, time)),
+            ],
+        }
+    }
+}
+
+/// Възел в дървото на ограниченията.
+///
+struct Node<'a> {
+    constraints: Vec<HashSet<Constraint<'a>>>,
+    routes: Vec<Vec<&'a Room>>,
+}
+
+impl<'a> Node<'a> {
+    fn cost(&self) -> usize {
+        self.routes.iter().map(|route| route.len() - 1).sum()
+    }
+}
+
+/// Стаята на агента в стъпка `time` -- след края на маршрута си той остава в последната стая.
+///
+fn room_at<'a>(route: &[&'a Room], time: usize) -> &'a Room {
+    route[usize::min(time, route.len() - 1)]
+}
+
+fn find_conflict<'a>(dungeon: &Dungeon, routes: &[Vec<&'a Room>]) -> Option<Conflict<'a>> {
+    let makespan = routes.iter().map(Vec::len).max().unwrap_or(0);
+
+    for time in 0..makespan {
+        for (agent, route) in routes.iter().enumerate() {
+            let room = room_at(route, time);
+            let agents = (0..routes.len())
+                .filter(|&other| room_at(&routes[other], time).name == room.name)
+                .collect::<Vec<_>>();
+
+            let capacity = dungeon.room_capacity(&room.name);
+            if agents.len() > capacity && agents[0] == agent {
+                let involved = agents[..=capacity].to_vec();
+                return Some(Conflict::Capacity(&room.name, time, involved));
+            }
+        }
+
+        if time == 0 {
+            continue;
+        }
+        for first in 0..routes.len() {
+            for second in first + 1..routes.len() {
+                let (from, to) = (room_at(&routes[first], time - 1), room_at(&routes[first], time));
+                if from.name != to.name
+                    && room_at(&routes[second], time - 1).name == to.name
+                    && room_at(&routes[second], time).name == from.name
+                {
+                    return Some(Conflict::Swap(first, second, &from.name, &to.name, time - 1));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+impl Dungeon {
+    /// Задава колко агента могат да стоят едновременно в стаята `room_name`. По подразбиране
+    /// всяка стая побира един агент.
+    ///
+    /// Ако няма такава стая, очакваме `Errors::UnknownRoom`.
+    ///
+    pub fn set_capacity(&mut self, room_name: &str, capacity: usize) -> Result<(), Errors> {
+        let room = self.get_room(room_name)?;
+        self.capacities.insert(room.name.clone(), capacity);
+        Ok(())
+    }
+
+    /// Колко агента могат да стоят едновременно в стаята `room_name`.
+    ///
+    /// Ако няма такава стая, очакваме `Errors::UnknownRoom`.
+    ///
+    pub fn capacity(&self, room_name: &str) -> Result<usize, Errors> {
+        Ok(self.room_capacity(&self.get_room(room_name)?.name))
+    }
+
+    fn room_capacity(&self, room_name: &str) -> usize {
+        self.capacities.get(room_name).copied().unwrap_or(1)
+    }
+
+    /// Планира маршрути за всички `agents` в рамките на `max_steps` стъпки. На всяка стъпка агентът
+    /// или минава в съседна стая, или чака на място. В никой момент в една стая няма повече агенти
+    /// от `capacity` ѝ, и два агента не си разменят местата през една и съща врата. Стигнал до
+    /// целта си, агентът остава там.
+    ///
+    /// Използваме Conflict-Based Search -- всеки агент се планира сам, а при сблъсък търсенето се
+    /// разклонява с ограничение за всеки от замесените агенти. Намерените маршрути са с най-малък
+    /// общ брой стъпки.
+    ///
+    /// Ограничението за брой агенти важи и в началната стъпка -- агенти с обща начална стая се
+    /// побират там само ако сме ѝ дали достатъчно `capacity` със `set_capacity`. Същото важи и за
+    /// обща крайна стая, в която агентите остават.
+    ///
+    /// Резултатът е по един маршрут за агент -- стаята му на всяка стъпка, като всички маршрути са
+    /// с еднаква дължина. Ако не намерим маршрути, връщаме `Ok(None)` -- включително когато сме
+    /// разгледали `MAX_NODES` възела без успех. Ако някоя стая не съществува, очакваме
+    /// `Errors::UnknownRoom`.
+    ///
+    pub fn plan_routes(
+        &self,
+        agents: &[Agent],
+        max_steps: usize,
+    ) -> Result<Option<Vec<Vec<&Room>>>, Errors> {
+        let waypoints = agents
+            .iter()
+            .map(|agent| {
+                agent
+                    .waypoints
+                    .iter()
+                    .map(|name| self.get_room(name))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Стаите, в които агентите започват или остават накрая, трябва да ги побират -- иначе
+        // търсенето би изчерпало всички разклонения, преди да разбере, че няма решение
+        let starts = waypoints.iter().map(|agent_waypoints| agent_waypoints[0]);
+        let goals = waypoints.iter().map(|agent_waypoints| agent_waypoints[agent_waypoints.len() - 1]);
+        if self.overfull(starts) || self.overfull(goals) {
+            return Ok(None);
+        }
+
+        let constraints = vec![HashSet::new(); agents.len()];
+        let mut routes = Vec::with_capacity(agents.len());
+        for (agent, agent_waypoints) in waypoints.iter().enumerate() {
+            match self.plan_agent(agent_waypoints, &constraints[agent], max_steps)? {
+                Some(route) => routes.push(route),
+                None => return Ok(None),
+            }
+        }
+
+        let mut nodes = vec![Node { constraints, routes }];
+        let mut open = BinaryHeap::new();
+        open.push(Reverse((nodes[0].cost(), 0)));
+
+        while let Some(Reverse((_, index))) = open.pop() {
+            if nodes.len() > MAX_NODES {
+                break;
+            }
+
+            let conflict = match find_conflict(self, &nodes[index].routes) {
+                Some(conflict) => conflict,
+                None => {
+                    let mut routes = std::mem::take(&mut nodes[index].routes);
+                    let makespan = routes.iter().map(Vec::len).max().unwrap_or(0);
+                    for route in routes.iter_mut() {
+                        let goal = route[route.len() - 1];
+                        route.resize(makespan, goal);
+                    }
+                    return Ok(Some(routes));
+                }
+            };
+
+            for (agent, constraint) in conflict.branches() {
+                let mut constraints = nodes[index].constraints.clone();
+                constraints[agent].insert(constraint);
+
+                if let Some(route) = self.plan_agent(&waypoints[agent], &constraints[agent], max_steps)? {
+                    let mut routes = nodes[index].routes.clone();
+                    routes[agent] = route;
+
+                    let child = Node { constraints, routes };
+                    open.push(Reverse((child.cost(), nodes.len())));
+                    nodes.push(child);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Дали някоя стая се среща в `rooms` повече пъти, отколкото побира.
+    ///
+    fn overfull<'a>(&self, rooms: impl Iterator<Item = &'a Room>) -> bool {
+        let mut counts = HashMap::<&str, usize>::new();
+        for room in rooms {
+            *counts.entry(&room.name).or_insert(0) += 1;
+        }
+        counts.iter().any(|(name, &count)| count > self.room_capacity(name))
+    }
+
+    /// Търсене в ширина по тройки (стая, стъпка, следваща междинна стая) за един агент, който
+    /// спазва `constraints`. Връща стаята на агента за всяка стъпка до пристигането му.
+    ///
+    fn plan_agent<'a>(
+        &'a self,
+        waypoints: &[&'a Room],
+        constraints: &HashSet<Constraint>,
+        max_steps: usize,
+    ) -> Result<Option<Vec<&'a Room>>, Errors> {
+        let advance = |mut next: usize, room: &Room| {
+            while next < waypoints.len() && waypoints[next].name == room.name {
+                next += 1;
+            }
+            next
+        };
+
+        let start = waypoints[0];
+        if constraints.contains(&Constraint::Room(&start.name, 0)) {
+            return Ok(None);
+        }
+
+        let mut q = VecDeque::new();
+        let mut parents = HashMap::<(&str, usize, usize), (&Room, usize)>::new();
+        q.push_back((start, 0, advance(1, start)));
+
+        while let Some((current, time, next)) = q.pop_front() {
+            let done = next == waypoints.len()
+                && (time..=max_steps).all(|t| !constraints.contains(&Constraint::Room(&current.name, t)));
+
+            if done {
+                let mut route = VecDeque::new();
+                let mut step = (current, time, next);
+                route.push_front(step.0);
+                while step.1 > 0 {
+                    let (room, waypoint) = parents[&(step.0.name.as_str(), step.1, step.2)];
+                    step = (room, step.1 - 1, waypoint);
+                    route.push_front(step.0);
+                }
+                return Ok(Some(route.into()));
+            }
+            if time == max_steps {
+                continue;
+            }
+
+            let mut next_rooms = vec![current];
+            for name in current.neighbours() {
+                next_rooms.push(self.get_room(name)?);
+            }
+
+            for room in next_rooms {
+                let waypoint = advance(next, room);
+                let key = (room.name.as_str(), time + 1, waypoint);
+                if parents.contains_key(&key)
+                    || constraints.contains(&Constraint::Room(&room.name, time + 1))
+                    || constraints.contains(&Constraint::Move(&current.name, &room.name, time))
+                {
+                    continue;
+                }
+                parents.insert(key, (current, next));
+                q.push_back((room, time + 1, waypoint));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod custom_tests {
+    use super::*;
+    use crate::Direction;
+
+    fn names(route: &[&Room]) -> Vec<String> {
+        route.iter().map(|room| room.name.clone()).collect()
+    }
+
+    // Коридор A - B - C със странична ниша N над B
+    fn corridor() -> Dungeon {
+        let mut dungeon = Dungeon::new();
+        for name in ["A", "B", "C", "N"] {
+            dungeon.add_room(name).unwrap();
+        }
+        dungeon.set_link("A", Direction::East, "B").unwrap();
+        dungeon.set_link("B", Direction::East, "C").unwrap();
+        dungeon.set_link("B", Direction::North, "N").unwrap();
+        dungeon
+    }
+
+    #[test]
+    fn test_routes_head_on() {
+        let dungeon = corridor();
+        let agents = [Agent::new("A", "C"), Agent::new("C", "A")];
+
+        let routes = dungeon.plan_routes(&agents, 10).unwrap().unwrap();
+        assert_eq!(routes[0].len(), routes[1].len());
+        assert_eq!(names(&routes[0]).last().unwrap(), "C");
+        assert_eq!(names(&routes[1]).last().unwrap(), "A");
+
+        for time in 0..routes[0].len() {
+            assert_ne!(routes[0][time].name, routes[1][time].name);
+            if time > 0 {
+                let swapped = routes[0][time].name == routes[1][time - 1].name
+                    && routes[1][time].name == routes[0][time - 1].name;
+                assert!(!swapped);
+            }
+        }
+        assert!(names(&routes[1]).contains(&String::from("N")));
+    }
+
+    #[test]
+    fn test_routes_capacity() {
+        let mut dungeon = corridor();
+        assert!(dungeon.plan_routes(&[Agent::new("A", "B"), Agent::new("A", "C")], 10).unwrap().is_none());
+
+        dungeon.set_capacity("A", 2).unwrap();
+        let routes = dungeon.plan_routes(&[Agent::new("A", "B"), Agent::new("A", "C")], 10).unwrap().unwrap();
+        assert_eq!(names(&routes[0]), vec!["A", "A", "B"]);
+        assert_eq!(names(&routes[1]), vec!["A", "B", "C"]);
+
+        assert_eq!(dungeon.capacity("A").unwrap(), 2);
+        assert_eq!(dungeon.capacity("B").unwrap(), 1);
+        assert!(dungeon.capacity("X").is_err());
+        assert!(dungeon.set_capacity("X", 2).is_err());
+        assert!(dungeon.plan_routes(&[Agent::new("A", "X")], 10).is_err());
+    }
+
+    #[test]
+    fn test_routes_patrol() {
+        let dungeon = corridor();
+        let agents = [Agent::new("A", "A").via("C"), Agent::new("N", "N")];
+
+        let routes = dungeon.plan_routes(&agents, 10).unwrap().unwrap();
+        assert_eq!(names(&routes[0]), vec!["A", "B", "C", "B", "A"]);
+        assert_eq!(names(&routes[1]), vec!["N"; 5]);
+
+        assert!(dungeon.plan_routes(&agents, 3).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_routes_unsolvable() {
+        // Без нишата двама агента не могат да се разминат в коридора, колкото и стъпки да имат
+        let mut dungeon = Dungeon::new();
+        for name in ["A", "B", "C"] {
+            dungeon.add_room(name).unwrap();
+        }
+        dungeon.set_link("A", Direction::East, "B").unwrap();
+        dungeon.set_link("B", Direction::East, "C").unwrap();
+
+        let agents = [Agent::new("A", "C"), Agent::new("C", "A")];
+        assert!(dungeon.plan_routes(&agents, 200).unwrap().is_none());
+
+        // Обща крайна стая, която не ги побира
+        let agents = [Agent::new("A", "B"), Agent::new("C", "B")];
+        assert!(dungeon.plan_routes(&agents, 200).unwrap().is_none());
+    }
+}